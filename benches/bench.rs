@@ -1,3 +1,12 @@
+//! Every benchmark reports its throughput in bytes/s. For generating numbers,
+//! this is the number of random bytes generated, for jumping and seeding it is
+//! the size of the state of the generator.
+//!
+//! The multi-lane generators select their AVX2 version at runtime, with or
+//! without the `std` feature, so the bulk benchmarks measure it on CPUs
+//! supporting AVX2. On other CPUs, they measure the fallback, which is slower
+//! for most of the multi-lane generators, see the crate documentation.
+
 #![allow(unknown_lints)]
 
 #[macro_use]
@@ -7,26 +16,278 @@ extern crate rand_core;
 
 use std::mem::size_of;
 use bencher::{black_box, Bencher};
-use xoshiro::Xoshiro128StarStar;
-use rand_core::{SeedableRng, RngCore};
+use xoshiro::{Xoshiro128StarStar, Xoshiro256StarStar};
+use xoshiro::{Xoshiro256StarStarX4, Xoshiro256StarStarX8, Xoshiro256PlusX8};
+use xoshiro::{Xoshiro128StarStarX8, Xoshiro128StarStarX16, Xoshiro128PlusX8};
+use xoshiro::{Xoroshiro64StarStarX8, Xoroshiro64StarStarX16};
+use rand_core::RngCore;
+use rand_core::block::{BlockRng, BlockRng64};
+
+/// The number of numbers generated per iteration by the `next_*` benchmarks.
+const CALLS: usize = 10;
+
+/// Define a module of benchmarks for the generator `$rng`.
+macro_rules! bench_rng {
+    ($m:ident, $rng:ident) => {
+        bench_rng!($m, $rng, {});
+    };
+    ($m:ident, $rng:ident, jumps) => {
+        bench_rng!($m, $rng, {
+            pub fn jump(b: &mut Bencher) {
+                let mut rng = Rng::from_seed_u64(0);
+                b.iter(|| {
+                    rng.jump();
+                    black_box(&rng);
+                });
+                b.bytes = size_of::<Rng>() as u64;
+            }
+
+            pub fn long_jump(b: &mut Bencher) {
+                let mut rng = Rng::from_seed_u64(0);
+                b.iter(|| {
+                    rng.long_jump();
+                    black_box(&rng);
+                });
+                b.bytes = size_of::<Rng>() as u64;
+            }
+        });
+    };
+    ($m:ident, $rng:ident, { $($extra:item)* }) => {
+        mod $m {
+            use super::*;
+
+            type Rng = xoshiro::$rng;
+
+            pub fn next_u32(b: &mut Bencher) {
+                let mut rng = Rng::from_seed_u64(0);
+                b.iter(|| {
+                    for _ in 0..CALLS {
+                        black_box(rng.next_u32());
+                    }
+                });
+                b.bytes = (size_of::<u32>() * CALLS) as u64;
+            }
+
+            pub fn next_u64(b: &mut Bencher) {
+                let mut rng = Rng::from_seed_u64(0);
+                b.iter(|| {
+                    for _ in 0..CALLS {
+                        black_box(rng.next_u64());
+                    }
+                });
+                b.bytes = (size_of::<u64>() * CALLS) as u64;
+            }
+
+            fn fill_bytes(b: &mut Bencher, buf: &mut [u8]) {
+                let mut rng = Rng::from_seed_u64(0);
+                b.iter(|| {
+                    rng.fill_bytes(buf);
+                    black_box(&buf);
+                });
+                b.bytes = buf.len() as u64;
+            }
+
+            pub fn fill_bytes_16(b: &mut Bencher) {
+                fill_bytes(b, &mut [0; 16]);
+            }
+
+            pub fn fill_bytes_256(b: &mut Bencher) {
+                fill_bytes(b, &mut [0; 256]);
+            }
+
+            pub fn fill_bytes_4096(b: &mut Bencher) {
+                fill_bytes(b, &mut [0; 4096]);
+            }
+
+            pub fn from_seed_u64(b: &mut Bencher) {
+                let mut seed = 0u64;
+                b.iter(|| {
+                    seed = seed.wrapping_add(1);
+                    black_box(Rng::from_seed_u64(seed));
+                });
+                b.bytes = size_of::<Rng>() as u64;
+            }
+
+            $($extra)*
+        }
+    };
+}
+
+/// Define a group of the benchmarks in the module `$m`.
+macro_rules! bench_group {
+    ($group:ident, $m:ident) => {
+        benchmark_group!($group, $m::next_u32, $m::next_u64,
+                         $m::fill_bytes_16, $m::fill_bytes_256,
+                         $m::fill_bytes_4096, $m::from_seed_u64);
+    };
+    ($group:ident, $m:ident, jumps) => {
+        benchmark_group!($group, $m::next_u32, $m::next_u64,
+                         $m::fill_bytes_16, $m::fill_bytes_256,
+                         $m::fill_bytes_4096, $m::from_seed_u64,
+                         $m::jump, $m::long_jump);
+    };
+}
+
+bench_rng!(splitmix64, SplitMix64);
+bench_rng!(xoshiro128starstar, Xoshiro128StarStar, jumps);
+bench_rng!(xoshiro128plus, Xoshiro128Plus, jumps);
+bench_rng!(xoshiro128plusplus, Xoshiro128PlusPlus, jumps);
+bench_rng!(xoshiro256starstar, Xoshiro256StarStar, jumps);
+bench_rng!(xoshiro256plus, Xoshiro256Plus, jumps);
+bench_rng!(xoshiro256plusplus, Xoshiro256PlusPlus, jumps);
+bench_rng!(xoshiro512starstar, Xoshiro512StarStar, jumps);
+bench_rng!(xoshiro512plus, Xoshiro512Plus, jumps);
+bench_rng!(xoshiro512plusplus, Xoshiro512PlusPlus, jumps);
+bench_rng!(xoroshiro64star, Xoroshiro64Star, jumps);
+bench_rng!(xoroshiro64starstar, Xoroshiro64StarStar, jumps);
+bench_rng!(xoroshiro128plus, Xoroshiro128Plus, jumps);
+bench_rng!(xoroshiro128starstar, Xoroshiro128StarStar, jumps);
+bench_rng!(xoroshiro128plusplus, Xoroshiro128PlusPlus, jumps);
+bench_rng!(xoroshiro1024star, Xoroshiro1024Star, jumps);
+bench_rng!(xoroshiro1024starstar, Xoroshiro1024StarStar, jumps);
+bench_rng!(xoroshiro1024plusplus, Xoroshiro1024PlusPlus, jumps);
+
+bench_group!(splitmix64_benches, splitmix64);
+bench_group!(xoshiro128starstar_benches, xoshiro128starstar, jumps);
+bench_group!(xoshiro128plus_benches, xoshiro128plus, jumps);
+bench_group!(xoshiro128plusplus_benches, xoshiro128plusplus, jumps);
+bench_group!(xoshiro256starstar_benches, xoshiro256starstar, jumps);
+bench_group!(xoshiro256plus_benches, xoshiro256plus, jumps);
+bench_group!(xoshiro256plusplus_benches, xoshiro256plusplus, jumps);
+bench_group!(xoshiro512starstar_benches, xoshiro512starstar, jumps);
+bench_group!(xoshiro512plus_benches, xoshiro512plus, jumps);
+bench_group!(xoshiro512plusplus_benches, xoshiro512plusplus, jumps);
+bench_group!(xoroshiro64star_benches, xoroshiro64star, jumps);
+bench_group!(xoroshiro64starstar_benches, xoroshiro64starstar, jumps);
+bench_group!(xoroshiro128plus_benches, xoroshiro128plus, jumps);
+bench_group!(xoroshiro128starstar_benches, xoroshiro128starstar, jumps);
+bench_group!(xoroshiro128plusplus_benches, xoroshiro128plusplus, jumps);
+bench_group!(xoroshiro1024star_benches, xoroshiro1024star, jumps);
+bench_group!(xoroshiro1024starstar_benches, xoroshiro1024starstar, jumps);
+bench_group!(xoroshiro1024plusplus_benches, xoroshiro1024plusplus, jumps);
+
+macro_rules! make_bench_u64 {
+    ($name:ident, $rng:expr) => {
+        fn $name(b: &mut Bencher) {
+            let mut rng = $rng;
+            b.iter(|| {
+                for _ in 0..CALLS {
+                    black_box(rng.next_u64());
+                }
+            });
+            b.bytes = (size_of::<u64>() * CALLS) as u64;
+        }
+    }
+}
 
 macro_rules! make_bench_u32 {
-    ($name:ident, $rng:path) => {
+    ($name:ident, $rng:expr) => {
         fn $name(b: &mut Bencher) {
-            type Rng = $rng;
-            let mut rng = Rng::from_seed([1, 0, 0, 0, 2, 0, 0, 0,
-                                          3, 0, 0, 0, 4, 0, 0, 0]);
+            let mut rng = $rng;
             b.iter(|| {
-                for _ in 0..10 {
+                for _ in 0..CALLS {
                     black_box(rng.next_u32());
                 }
             });
-            b.bytes = size_of::<u32>() as u64;
+            b.bytes = (size_of::<u32>() * CALLS) as u64;
+        }
+    }
+}
+
+make_bench_u64!(rand_u64_xoshiro256_block,
+                BlockRng64::new(Xoshiro256StarStar::from_seed_u64(0)));
+make_bench_u64!(rand_u64_xoshiro256_x8_block,
+                BlockRng64::new(Xoshiro256StarStarX8::from_seed_u64(0)));
+make_bench_u32!(rand_u32_xoshiro128_block,
+                BlockRng::new(Xoshiro128StarStar::from_seed_u64(0)));
+
+/// The number of words filled per iteration by the bulk benchmarks.
+const FILL_LEN: usize = 1024;
+
+macro_rules! make_bench_fill {
+    ($name:ident, $rng:expr, $ty:ty, |$r:ident, $buf:ident| $fill:expr) => {
+        fn $name(b: &mut Bencher) {
+            let mut $r = $rng;
+            let mut $buf = [<$ty>::default(); FILL_LEN];
+            b.iter(|| {
+                $fill;
+                black_box(&$buf);
+            });
+            b.bytes = (size_of::<$ty>() * FILL_LEN) as u64;
         }
     }
 }
 
-make_bench_u32!(rand_u32_xoshiro, Xoshiro128StarStar);
+make_bench_fill!(fill_bytes_xoshiro256,
+                 Xoshiro256StarStar::from_seed_u64(0), u8,
+                 |rng, buf| rng.fill_bytes(&mut buf));
+make_bench_fill!(fill_bytes_xoshiro256_block,
+                 BlockRng64::new(Xoshiro256StarStar::from_seed_u64(0)), u8,
+                 |rng, buf| rng.fill_bytes(&mut buf));
+make_bench_fill!(fill_bytes_xoshiro256_x8_block,
+                 BlockRng64::new(Xoshiro256StarStarX8::from_seed_u64(0)), u8,
+                 |rng, buf| rng.fill_bytes(&mut buf));
+make_bench_fill!(fill_u64_xoshiro256,
+                 Xoshiro256StarStar::from_seed_u64(0), u64,
+                 |rng, buf| rng.fill_u64(&mut buf));
+make_bench_fill!(fill_u64_xoshiro256plus_x8,
+                 Xoshiro256PlusX8::from_seed_u64(0), u64,
+                 |rng, buf| rng.fill_u64(&mut buf));
+make_bench_fill!(fill_f64_xoshiro256,
+                 Xoshiro256StarStar::from_seed_u64(0), f64,
+                 |rng, buf| rng.fill_f64(&mut buf));
+make_bench_fill!(fill_u64_xoshiro256_x4,
+                 Xoshiro256StarStarX4::from_seed_u64(0), u64,
+                 |rng, buf| rng.fill_u64(&mut buf));
+make_bench_fill!(fill_u64_xoshiro256_x8,
+                 Xoshiro256StarStarX8::from_seed_u64(0), u64,
+                 |rng, buf| rng.fill_u64(&mut buf));
+make_bench_fill!(fill_bytes_xoshiro128,
+                 Xoshiro128StarStar::from_seed_u64(0), u8,
+                 |rng, buf| rng.fill_bytes(&mut buf));
+make_bench_fill!(fill_bytes_xoshiro128_block,
+                 BlockRng::new(Xoshiro128StarStar::from_seed_u64(0)), u8,
+                 |rng, buf| rng.fill_bytes(&mut buf));
+make_bench_fill!(fill_u32_xoshiro128,
+                 Xoshiro128StarStar::from_seed_u64(0), u32,
+                 |rng, buf| rng.fill_u32(&mut buf));
+make_bench_fill!(fill_u32_xoshiro128_x8,
+                 Xoshiro128StarStarX8::from_seed_u64(0), u32,
+                 |rng, buf| rng.fill_u32(&mut buf));
+make_bench_fill!(fill_u32_xoshiro128_x16,
+                 Xoshiro128StarStarX16::from_seed_u64(0), u32,
+                 |rng, buf| rng.fill_u32(&mut buf));
+make_bench_fill!(fill_u32_xoshiro128plus_x8,
+                 Xoshiro128PlusX8::from_seed_u64(0), u32,
+                 |rng, buf| rng.fill_u32(&mut buf));
+make_bench_fill!(fill_u32_xoroshiro64_x8,
+                 Xoroshiro64StarStarX8::from_seed_u64(0), u32,
+                 |rng, buf| rng.fill_u32(&mut buf));
+make_bench_fill!(fill_u32_xoroshiro64_x16,
+                 Xoroshiro64StarStarX16::from_seed_u64(0), u32,
+                 |rng, buf| rng.fill_u32(&mut buf));
+make_bench_fill!(fill_f32_xoshiro128,
+                 Xoshiro128StarStar::from_seed_u64(0), f32,
+                 |rng, buf| rng.fill_f32(&mut buf));
 
-benchmark_group!(benches, rand_u32_xoshiro);
-benchmark_main!(benches);
+benchmark_group!(bulk_benches,
+                 rand_u64_xoshiro256_block, rand_u64_xoshiro256_x8_block,
+                 rand_u32_xoshiro128_block, fill_bytes_xoshiro256,
+                 fill_bytes_xoshiro256_block, fill_bytes_xoshiro256_x8_block,
+                 fill_u64_xoshiro256, fill_f64_xoshiro256,
+                 fill_u64_xoshiro256_x4, fill_u64_xoshiro256_x8,
+                 fill_u64_xoshiro256plus_x8, fill_bytes_xoshiro128,
+                 fill_bytes_xoshiro128_block, fill_u32_xoshiro128,
+                 fill_u32_xoshiro128_x8, fill_u32_xoshiro128_x16,
+                 fill_u32_xoshiro128plus_x8, fill_u32_xoroshiro64_x8,
+                 fill_u32_xoroshiro64_x16, fill_f32_xoshiro128);
+benchmark_main!(splitmix64_benches,
+                xoshiro128starstar_benches, xoshiro128plus_benches,
+                xoshiro128plusplus_benches, xoshiro256starstar_benches,
+                xoshiro256plus_benches, xoshiro256plusplus_benches,
+                xoshiro512starstar_benches, xoshiro512plus_benches,
+                xoshiro512plusplus_benches, xoroshiro64star_benches,
+                xoroshiro64starstar_benches, xoroshiro128plus_benches,
+                xoroshiro128starstar_benches, xoroshiro128plusplus_benches,
+                xoroshiro1024star_benches, xoroshiro1024starstar_benches,
+                xoroshiro1024plusplus_benches, bulk_benches);
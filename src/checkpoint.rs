@@ -0,0 +1,186 @@
+//! A compact, versioned binary format for the state of a generator.
+//!
+//! See the crate documentation for the layout.
+
+use std::error::Error;
+use std::io::{self, Read, Write};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+/// The bytes every checkpoint starts with.
+pub const MAGIC: [u8; 4] = *b"XSHR";
+
+/// The version of the format written by this crate.
+pub const VERSION: u8 = 1;
+
+/// The length of the header preceding the state words.
+const HEADER_LEN: usize = 8;
+
+/// The maximal number of state words.
+const MAX_WORDS: usize = 16;
+
+pub const SPLITMIX64: u8 = 1;
+pub const XOROSHIRO64STAR: u8 = 2;
+pub const XOROSHIRO64STARSTAR: u8 = 3;
+pub const XOROSHIRO128PLUS: u8 = 4;
+pub const XOROSHIRO128STARSTAR: u8 = 5;
+pub const XOROSHIRO128PLUSPLUS: u8 = 6;
+pub const XOSHIRO128PLUS: u8 = 7;
+pub const XOSHIRO128STARSTAR: u8 = 8;
+pub const XOSHIRO128PLUSPLUS: u8 = 9;
+pub const XOSHIRO256PLUS: u8 = 10;
+pub const XOSHIRO256STARSTAR: u8 = 11;
+pub const XOSHIRO256PLUSPLUS: u8 = 12;
+pub const XOSHIRO512PLUS: u8 = 13;
+pub const XOSHIRO512STARSTAR: u8 = 14;
+pub const XOSHIRO512PLUSPLUS: u8 = 15;
+pub const XOROSHIRO1024STAR: u8 = 16;
+pub const XOROSHIRO1024STARSTAR: u8 = 17;
+pub const XOROSHIRO1024PLUSPLUS: u8 = 18;
+
+/// The names of the generators, indexed by their algorithm identifier.
+const NAMES: [&str; 19] = [
+    "an unknown generator", "SplitMix64", "Xoroshiro64Star",
+    "Xoroshiro64StarStar", "Xoroshiro128Plus", "Xoroshiro128StarStar",
+    "Xoroshiro128PlusPlus", "Xoshiro128Plus", "Xoshiro128StarStar",
+    "Xoshiro128PlusPlus", "Xoshiro256Plus", "Xoshiro256StarStar",
+    "Xoshiro256PlusPlus", "Xoshiro512Plus", "Xoshiro512StarStar",
+    "Xoshiro512PlusPlus", "Xoroshiro1024Star", "Xoroshiro1024StarStar",
+    "Xoroshiro1024PlusPlus",
+];
+
+/// Return the name of the generator with the given algorithm identifier.
+fn name(algorithm: u8) -> &'static str {
+    NAMES.get(algorithm as usize).cloned().unwrap_or(NAMES[0])
+}
+
+/// Return an error for a malformed checkpoint.
+pub fn invalid<E>(error: E) -> io::Error
+    where E: Into<Box<dyn Error + Send + Sync>>
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Calculate the CRC-32 (as used by zlib and PNG) of `bytes`.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= u32::from(b);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Write a checkpoint of the given state words, each `width` bytes wide.
+pub fn write<W: Write>(mut writer: W, algorithm: u8, width: usize,
+                       words: &[u64]) -> io::Result<()>
+{
+    debug_assert!(width == 4 || width == 8);
+    debug_assert!(words.len() <= MAX_WORDS);
+    let mut buf = [0; HEADER_LEN + 8 * MAX_WORDS + 4];
+    let len = HEADER_LEN + width * words.len();
+    buf[..4].copy_from_slice(&MAGIC);
+    buf[4] = VERSION;
+    buf[5] = algorithm;
+    buf[6] = width as u8;
+    buf[7] = words.len() as u8;
+    for (chunk, &w) in buf[HEADER_LEN..len].chunks_mut(width).zip(words) {
+        LittleEndian::write_uint(chunk, w, width);
+    }
+    let crc = crc32(&buf[..len]);
+    LittleEndian::write_u32(&mut buf[len..len + 4], crc);
+    writer.write_all(&buf[..len + 4])
+}
+
+/// Read a checkpoint into the given state words, each `width` bytes wide.
+///
+/// Fails if the checkpoint was written by a different generator or is
+/// corrupted.
+pub fn read<R: Read>(mut reader: R, algorithm: u8, width: usize,
+                     words: &mut [u64]) -> io::Result<()>
+{
+    debug_assert!(width == 4 || width == 8);
+    debug_assert!(words.len() <= MAX_WORDS);
+    let mut buf = [0; HEADER_LEN + 8 * MAX_WORDS + 4];
+    reader.read_exact(&mut buf[..HEADER_LEN])?;
+    if buf[..4] != MAGIC {
+        return Err(invalid("not a checkpoint of a xoshiro generator"));
+    }
+    if buf[4] != VERSION {
+        return Err(invalid(format!("unsupported checkpoint version {}",
+                                   buf[4])));
+    }
+    if buf[5] != algorithm {
+        return Err(invalid(format!("expected a checkpoint of {}, found {}",
+                                   name(algorithm), name(buf[5]))));
+    }
+    if buf[6] as usize != width || buf[7] as usize != words.len() {
+        return Err(invalid(format!(
+            "expected {} words of {} bytes, found {} words of {} bytes",
+            words.len(), width, buf[7], buf[6])));
+    }
+    let len = HEADER_LEN + width * words.len();
+    reader.read_exact(&mut buf[HEADER_LEN..len + 4])?;
+    if LittleEndian::read_u32(&buf[len..len + 4]) != crc32(&buf[..len]) {
+        return Err(invalid("checkpoint checksum mismatch"));
+    }
+    for (w, chunk) in words.iter_mut().zip(buf[HEADER_LEN..len].chunks(width)) {
+        *w = LittleEndian::read_uint(chunk, width);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_check() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    }
+
+    #[test]
+    fn layout() {
+        let mut buf = Vec::new();
+        write(&mut buf, XOROSHIRO64STAR, 4, &[1, 0xdeadbeef]).unwrap();
+        assert_eq!(&buf[..16], &[
+            b'X', b'S', b'H', b'R', 1, XOROSHIRO64STAR, 4, 2,
+            1, 0, 0, 0, 0xef, 0xbe, 0xad, 0xde,
+        ]);
+        assert_eq!(LittleEndian::read_u32(&buf[16..]), crc32(&buf[..16]));
+
+        let mut words = [0; 2];
+        read(&buf[..], XOROSHIRO64STAR, 4, &mut words).unwrap();
+        assert_eq!(words, [1, 0xdeadbeef]);
+    }
+
+    #[test]
+    fn invalid_checkpoints() {
+        let mut buf = Vec::new();
+        write(&mut buf, XOSHIRO256PLUS, 8, &[1, 2, 3, 4]).unwrap();
+        let mut words = [0; 4];
+        let kind = |r: io::Result<()>| r.unwrap_err().kind();
+
+        let e = read(&buf[..], XOSHIRO256STARSTAR, 8, &mut words).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(e.to_string(), "expected a checkpoint of \
+                                   Xoshiro256StarStar, found Xoshiro256Plus");
+        assert_eq!(kind(read(&buf[..], XOSHIRO256PLUS, 8, &mut words[..2])),
+                   io::ErrorKind::InvalidData);
+        assert_eq!(kind(read(&buf[..buf.len() - 1], XOSHIRO256PLUS, 8,
+                             &mut words)),
+                   io::ErrorKind::UnexpectedEof);
+        for &i in &[0, 4, 20] {
+            let mut corrupted = buf.clone();
+            corrupted[i] ^= 1;
+            assert_eq!(kind(read(&corrupted[..], XOSHIRO256PLUS, 8,
+                                 &mut words)),
+                       io::ErrorKind::InvalidData);
+        }
+        read(&buf[..], XOSHIRO256PLUS, 8, &mut words).unwrap();
+        assert_eq!(words, [1, 2, 3, 4]);
+    }
+}
@@ -6,6 +6,97 @@ macro_rules! from_splitmix {
     } }
 }
 
+/// Initialize a RNG from a byte string of arbitrary length, by mixing it into
+/// a seed.
+macro_rules! from_seed_bytes {
+    ($bytes:expr) => { {
+        let mut seed = <Self as ::rand_core::SeedableRng>::Seed::default();
+        ::splitmix64::seed_from_bytes($bytes, seed.as_mut());
+        Self::from_seed(seed)
+    } }
+}
+
+/// Initialize a RNG from a `u128` seed using a `SeedSequence`.
+macro_rules! from_seed_u128 {
+    ($seed:expr) => {
+        Self::from_seed_sequence(
+            &::SeedSequence::new(&[$seed as u64, ($seed >> 64) as u64]))
+    }
+}
+
+/// Implement the seeding methods shared by all RNGs, given the number of bits
+/// of state and, for generators rejecting an all-zero state, the type of the
+/// seed.
+macro_rules! impl_seeding {
+    (@u128 64) => {
+"Unlike with `from_seed_u64`, all bits of the seed are used. However, the
+state has only 64 bits, so distinct seeds can result in the same state."
+    };
+    (@u128 128) => {
+"Unlike with `from_seed_u64`, all bits of the seed are used. The seed is hashed
+to the 128 bits of state, so distinct seeds are not guaranteed to result in
+distinct states, although collisions are very unlikely."
+    };
+    (@u128 256) => {
+"Unlike with `from_seed_u64`, all bits of the seed are used, and they are
+mapped injectively to the 256 bits of state, so distinct seeds result in
+distinct states."
+    };
+    (@u128 $bits:tt) => {
+        concat!(
+"Unlike with `from_seed_u64`, all bits of the seed are used, and they are
+mapped injectively to the first 256 of the ", stringify!($bits), " bits of
+state, so distinct seeds result in distinct states.")
+    };
+    ($Rng:ident, $bits:tt, $Seed:ty) => {
+        #[doc = concat!("Create a new `", stringify!($Rng),
+                        "` from a seed, failing if the seed is entirely 0.")]
+        ///
+        /// Unlike `from_seed`, this does not replace such a seed by a different
+        /// one.
+        pub fn try_from_seed(seed: $Seed) -> Result<$Rng, ::ZeroStateError> {
+            if seed.iter().all(|&x| x == 0) {
+                return Err(::ZeroStateError);
+            }
+            Ok(<$Rng as ::rand_core::SeedableRng>::from_seed(seed))
+        }
+
+        impl_seeding!($Rng, $bits);
+    };
+    ($Rng:ident, $bits:tt) => {
+        #[doc = concat!("Seed a `", stringify!($Rng),
+                        "` from a byte string of arbitrary length.")]
+        ///
+        /// The bytes are mixed into a full seed with a function based on
+        /// `SplitMix64`, so long byte strings can provide more entropy than
+        /// `from_seed_u64`. The result does not depend on the platform.
+        pub fn from_seed_bytes(bytes: &[u8]) -> $Rng {
+            from_seed_bytes!(bytes)
+        }
+
+        #[doc = concat!("Seed a `", stringify!($Rng), "` from a string, such \
+                         as a human-readable name of an experiment.")]
+        ///
+        /// This is the same as `from_seed_bytes` with the UTF-8 bytes of `seed`.
+        pub fn from_str_seed(seed: &str) -> $Rng {
+            $Rng::from_seed_bytes(seed.as_bytes())
+        }
+
+        #[doc = concat!("Seed a `", stringify!($Rng),
+                        "` from a `u128` using a `SeedSequence`.\n\n",
+                        impl_seeding!(@u128 $bits))]
+        pub fn from_seed_u128(seed: u128) -> $Rng {
+            from_seed_u128!(seed)
+        }
+
+        #[doc = concat!("Seed a `", stringify!($Rng),
+                        "` from a `SeedSequence`.")]
+        pub fn from_seed_sequence(seq: &::SeedSequence) -> $Rng {
+            seq.seed_rng()
+        }
+    };
+}
+
 /// Apply the ** scrambler used by some RNGs from the xoshiro family.
 macro_rules! starstar_u64 {
     ($x:expr) => {
@@ -20,6 +111,13 @@ macro_rules! starstar_u32 {
     }
 }
 
+/// Apply the ++ scrambler used by some RNGs from the xoshiro family.
+macro_rules! plusplus {
+    ($x:expr, $y:expr, $rot:expr) => {
+        $x.wrapping_add($y).rotate_left($rot).wrapping_add($x)
+    }
+}
+
 /// Implement a jump function for an RNG from the xoshiro family.
 macro_rules! impl_jump {
     (u32, $self:expr, [$j0:expr, $j1:expr]) => {
@@ -119,6 +217,377 @@ macro_rules! impl_jump {
         }
         $self.s = s;
     };
+    (u64, $self:expr, [$j0:expr, $j1:expr, $j2:expr, $j3:expr,
+                       $j4:expr, $j5:expr, $j6:expr, $j7:expr,
+                       $j8:expr, $j9:expr, $j10:expr, $j11:expr,
+                       $j12:expr, $j13:expr, $j14:expr, $j15:expr]) => {
+        const JUMP: [u64; 16] = [$j0, $j1, $j2, $j3, $j4, $j5, $j6, $j7,
+                                 $j8, $j9, $j10, $j11, $j12, $j13, $j14, $j15];
+        apply_jump_poly!(s p, $self, next_u64, JUMP);
+    };
+}
+
+/// Apply a jump polynomial computed by `gf2` to the state of an RNG from the
+/// xoshiro family.
+macro_rules! apply_jump_poly {
+    (s, $self:expr, $next:ident, $poly:expr) => {
+        let mut s = [0; 8];
+        let s = &mut s[..$self.s.len()];
+        for j in $poly.iter() {
+            for b in 0..64 {
+                if (j & 1 << b) != 0 {
+                    for (t, &x) in s.iter_mut().zip($self.s.iter()) {
+                        *t ^= x;
+                    }
+                }
+                $self.$next();
+            }
+        }
+        $self.s.copy_from_slice(s);
+    };
+    (s0 s1, $self:expr, $next:ident, $poly:expr) => {
+        let mut s0 = 0;
+        let mut s1 = 0;
+        for j in $poly.iter() {
+            for b in 0..64 {
+                if (j & 1 << b) != 0 {
+                    s0 ^= $self.s0;
+                    s1 ^= $self.s1;
+                }
+                $self.$next();
+            }
+        }
+        $self.s0 = s0;
+        $self.s1 = s1;
+    };
+    (s p, $self:expr, $next:ident, $poly:expr) => {
+        // The state is a circular buffer starting at `p`.
+        let mut t = [0; 16];
+        for j in $poly.iter() {
+            for b in 0..64 {
+                if (j & 1 << b) != 0 {
+                    for (i, t) in t.iter_mut().enumerate() {
+                        *t ^= $self.s[(i + $self.p) & 15];
+                    }
+                }
+                $self.$next();
+            }
+        }
+        for (i, &t) in t.iter().enumerate() {
+            $self.s[(i + $self.p) & 15] = t;
+        }
+    };
+}
+
+/// Implement the methods generating floats, given the type of the words
+/// natively generated by the RNG.
+///
+/// Only the upper bits of the words are used.
+macro_rules! impl_floats {
+    (@f64 u64, $self:ident) => {
+        ::rand_core::RngCore::next_u64($self) >> 11
+    };
+    (@f64 u32, $self:ident) => { {
+        let hi = ::rand_core::RngCore::next_u32($self) >> 5;
+        let lo = ::rand_core::RngCore::next_u32($self) >> 6;
+        u64::from(hi) << 26 | u64::from(lo)
+    } };
+    (@f32 u64, $self:ident) => {
+        (::rand_core::RngCore::next_u64($self) >> 40) as u32
+    };
+    (@f32 u32, $self:ident) => {
+        ::rand_core::RngCore::next_u32($self) >> 8
+    };
+    ($word:ident) => {
+        /// Return a random `f64` uniformly distributed in [0, 1).
+        ///
+        /// The number is a multiple of 2^-53 generated from the upper bits of
+        /// the output.
+        #[inline]
+        pub fn next_f64(&mut self) -> f64 {
+            ::float::f64_closed_open(impl_floats!(@f64 $word, self))
+        }
+
+        /// Return a random `f64` uniformly distributed in (0, 1].
+        ///
+        /// Like `next_f64`, but never returns 0.
+        #[inline]
+        pub fn next_f64_open_closed(&mut self) -> f64 {
+            ::float::f64_open_closed(impl_floats!(@f64 $word, self))
+        }
+
+        /// Return a random `f64` uniformly distributed in (0, 1).
+        ///
+        /// The number is an odd multiple of 2^-53.
+        #[inline]
+        pub fn next_f64_open(&mut self) -> f64 {
+            ::float::f64_open(impl_floats!(@f64 $word, self))
+        }
+
+        /// Return a random `f64` in [0, 1), such that every representable
+        /// number may be returned.
+        ///
+        /// Unlike `next_f64`, numbers close to 0 are not restricted to
+        /// multiples of 2^-53, instead each number is returned with a
+        /// probability proportional to the distance to the next larger one.
+        /// This consumes more output of the generator, but rarely more than
+        /// two words. Like the mantissa, the exponent is generated from the
+        /// upper bits of the output.
+        #[inline]
+        pub fn next_f64_full(&mut self) -> f64 {
+            let mantissa = impl_floats!(@f64 $word, self) >> 1;
+            ::float::f64_full(mantissa, 53,
+                              || impl_floats!(@f64 $word, self) << 11)
+        }
+
+        /// Return a random `f32` uniformly distributed in [0, 1).
+        ///
+        /// The number is a multiple of 2^-24 generated from the upper bits of
+        /// the output.
+        #[inline]
+        pub fn next_f32(&mut self) -> f32 {
+            ::float::f32_closed_open(impl_floats!(@f32 $word, self))
+        }
+
+        /// Return a random `f32` uniformly distributed in (0, 1].
+        ///
+        /// Like `next_f32`, but never returns 0.
+        #[inline]
+        pub fn next_f32_open_closed(&mut self) -> f32 {
+            ::float::f32_open_closed(impl_floats!(@f32 $word, self))
+        }
+
+        /// Return a random `f32` uniformly distributed in (0, 1).
+        ///
+        /// The number is an odd multiple of 2^-24.
+        #[inline]
+        pub fn next_f32_open(&mut self) -> f32 {
+            ::float::f32_open(impl_floats!(@f32 $word, self))
+        }
+
+        /// Return a random `f32` in [0, 1), such that every representable
+        /// number may be returned.
+        ///
+        /// See `next_f64_full`.
+        #[inline]
+        pub fn next_f32_full(&mut self) -> f32 {
+            let mantissa = impl_floats!(@f32 $word, self) >> 1;
+            ::float::f32_full(mantissa, 24,
+                              || impl_floats!(@f32 $word, self) << 8)
+        }
+    };
+}
+
+/// Implement the methods filling slices with random numbers.
+macro_rules! impl_fill {
+    (@fill $dest:ident, $next:expr) => { {
+        // Unrolled manually, the chunks have a known length, so there are no
+        // bounds checks.
+        let mut chunks = $dest.chunks_exact_mut(4);
+        for chunk in &mut chunks {
+            chunk[0] = $next;
+            chunk[1] = $next;
+            chunk[2] = $next;
+            chunk[3] = $next;
+        }
+        for x in chunks.into_remainder() {
+            *x = $next;
+        }
+    } };
+    () => {
+        /// Fill `dest` with random `u64`s, equivalently to calling
+        /// `next_u64()` for each element.
+        ///
+        /// Unlike `fill_bytes`, this writes the words directly, without
+        /// converting them to bytes.
+        pub fn fill_u64(&mut self, dest: &mut [u64]) {
+            impl_fill!(@fill dest, ::rand_core::RngCore::next_u64(self))
+        }
+
+        /// Fill `dest` with random `u32`s, equivalently to calling
+        /// `next_u32()` for each element.
+        pub fn fill_u32(&mut self, dest: &mut [u32]) {
+            impl_fill!(@fill dest, ::rand_core::RngCore::next_u32(self))
+        }
+
+        /// Fill `dest` with random `f64`s in [0, 1), equivalently to calling
+        /// `next_f64()` for each element.
+        pub fn fill_f64(&mut self, dest: &mut [f64]) {
+            impl_fill!(@fill dest, self.next_f64())
+        }
+
+        /// Fill `dest` with random `f32`s in [0, 1), equivalently to calling
+        /// `next_f32()` for each element.
+        pub fn fill_f32(&mut self, dest: &mut [f32]) {
+            impl_fill!(@fill dest, self.next_f32())
+        }
+    };
+}
+
+/// Implement `BlockRngCore` for an RNG, generating blocks of 32 words with
+/// `fill_u64` or `fill_u32`.
+macro_rules! impl_block_rng_core {
+    ($Self:ident, u64) => {
+        impl_block_rng_core!($Self, u64, fill_u64);
+    };
+    ($Self:ident, u32) => {
+        impl_block_rng_core!($Self, u32, fill_u32);
+    };
+    ($Self:ident, $word:ident, $fill:ident) => {
+        /// Generating whole blocks allows wrapping the generator in
+        /// `BlockRng64` (or `BlockRng` for 32-bit generators), which buffers
+        /// the output.
+        impl ::rand_core::block::BlockRngCore for $Self {
+            type Item = $word;
+            type Results = [$word; 32];
+
+            #[inline]
+            fn generate(&mut self, results: &mut [$word; 32]) {
+                self.$fill(results);
+            }
+        }
+    };
+}
+
+/// Implement moving an RNG from the xoshiro family by a number of steps, given
+/// the function from `gf2` calculating the jump polynomial and the
+/// characteristic polynomial of its linear engine.
+macro_rules! impl_advance {
+    ($($state:ident)+, $self:expr, $next:ident, $pow:ident($n:expr), $charpoly:expr) => {
+        let mut poly = $charpoly;
+        ::gf2::$pow($n, &$charpoly, &mut poly);
+        apply_jump_poly!($($state)+, $self, $next, poly);
+    }
+}
+
+/// Implement stepping an RNG from the xoshiro family backwards, given the type
+/// of the words it natively generates, the state fields and the characteristic
+/// polynomial as for `impl_advance`, and the block undoing one step of `self`
+/// and returning the undone output.
+///
+/// For 64-bit generators, `upper` or `lower` selects the bits returned by
+/// `prev_u32`, which have to be the same as for `next_u32`.
+macro_rules! impl_prev {
+    (@doc $next:ident) => {
+        concat!("Step backwards, undoing one call to `", stringify!($next),
+                "()`.\n\nReturns the number returned by the undone call.")
+    };
+    (@retreat $next:ident, $($state:ident)+, $charpoly:expr) => {
+        #[doc = concat!("Retreat the state, equivalently to undoing `n` calls \
+                         to `", stringify!($next), "()`.")]
+        ///
+        /// This is the inverse of `advance`.
+        pub fn retreat(&mut self, n: u128) {
+            impl_advance!($($state)+, self, $next, pow_x_inv(n), $charpoly);
+        }
+    };
+    (u64 upper, $($state:ident)+, $charpoly:expr,
+     |$self:ident| $prev:block) => {
+        #[doc = impl_prev!(@doc next_u32)]
+        pub fn prev_u32(&mut self) -> u32 {
+            // The lowest bits have some linear dependencies, so we use the
+            // upper bits instead.
+            (self.prev_u64() >> 32) as u32
+        }
+
+        #[doc = impl_prev!(@doc next_u64)]
+        pub fn prev_u64(&mut $self) -> u64 $prev
+
+        impl_prev!(@retreat next_u64, $($state)+, $charpoly);
+    };
+    (u64 lower, $($state:ident)+, $charpoly:expr,
+     |$self:ident| $prev:block) => {
+        #[doc = impl_prev!(@doc next_u32)]
+        pub fn prev_u32(&mut self) -> u32 {
+            self.prev_u64() as u32
+        }
+
+        #[doc = impl_prev!(@doc next_u64)]
+        pub fn prev_u64(&mut $self) -> u64 $prev
+
+        impl_prev!(@retreat next_u64, $($state)+, $charpoly);
+    };
+    (u32, $($state:ident)+, $charpoly:expr, |$self:ident| $prev:block) => {
+        #[doc = impl_prev!(@doc next_u32)]
+        pub fn prev_u32(&mut $self) -> u32 $prev
+
+        #[doc = impl_prev!(@doc next_u64)]
+        pub fn prev_u64(&mut self) -> u64 {
+            let high = u64::from(self.prev_u32());
+            let low = u64::from(self.prev_u32());
+            (high << 32) | low
+        }
+
+        impl_prev!(@retreat next_u32, $($state)+, $charpoly);
+    };
+}
+
+/// Implement splitting an RNG from the xoshiro family into non-overlapping
+/// streams, given the name of its `next_*` method and the base 2 logarithms of
+/// the number of jumps per long jump and of the length of a jump.
+///
+/// If not all `usize` streams fit into the period, the documentation of the
+/// panic and the function checking the number of streams follow.
+macro_rules! impl_streams {
+    (@body $self:expr, $n:expr, $log_jumps:expr) => { {
+        let mut streams = Vec::with_capacity($n);
+        let mut outer = $self.clone();
+        while streams.len() < $n {
+            let jumps = 1usize.checked_shl($log_jumps).unwrap_or(!0);
+            let block = ::core::cmp::min(jumps, $n - streams.len());
+            let mut inner = outer.clone();
+            for _ in 0..block {
+                streams.push(inner.clone());
+                inner.jump();
+            }
+            outer.long_jump();
+        }
+        streams
+    } };
+    ($Rng:ident, $next:ident, $log_jumps:tt, $jump:tt
+     $(, $panics:expr, $check:path)?) => {
+        /// Split the generator into `n` non-overlapping streams for parallel
+        /// computations.
+        ///
+        #[doc = concat!(
+"The first stream starts at the current state. Stream `i` is obtained by
+calling `long_jump()` `i / 2^", stringify!($log_jumps), "` times and `jump()` \
+`i % 2^", stringify!($log_jumps), "` times, so that each stream can produce
+2^", stringify!($jump), " numbers before overlapping with the next one.")]
+        $(
+        ///
+        /// # Panics
+        ///
+        #[doc = $panics]
+        )?
+        ///
+        #[doc = concat!("```
+# extern crate rand;
+# extern crate xoshiro;
+# fn main() {
+use rand::RngCore;
+use xoshiro::", stringify!($Rng), ";
+
+let rng = ", stringify!($Rng), "::from_seed_u64(0);
+for mut stream in rng.streams(4) {
+    // Each stream can be moved to a different thread.
+    stream.", stringify!($next), "();
+}
+# }
+```")]
+        #[cfg(feature = "std")]
+        pub fn streams(&self, n: usize) -> Vec<$Rng> {
+            $($check(n);)?
+            impl_streams!(@body self, n, $log_jumps)
+        }
+    };
+}
+
+/// Panic if `n` streams of 2^32 numbers do not fit into the period 2^64 - 1 of
+/// the xoroshiro64 generators without overlapping.
+#[cfg(feature = "std")]
+pub fn assert_xoroshiro64_streams(n: usize) {
+    assert!((n as u64) < 1 << 32, "too many streams");
 }
 
 /// Implement the xoroshiro iteration.
@@ -130,6 +599,15 @@ macro_rules! impl_xoroshiro_u32 {
     }
 }
 
+/// Implement the inverse of the xoroshiro iteration.
+macro_rules! impl_xoroshiro_u32_inv {
+    ($self:expr) => {
+        let t = $self.s1.rotate_right(13);
+        $self.s0 = ($self.s0 ^ t ^ (t << 9)).rotate_right(26);
+        $self.s1 = t ^ $self.s0;
+    }
+}
+
 /// Implement the xoroshiro iteration.
 macro_rules! impl_xoroshiro_u64 {
     ($self:expr) => {
@@ -139,6 +617,33 @@ macro_rules! impl_xoroshiro_u64 {
     }
 }
 
+/// Implement the inverse of the xoroshiro iteration.
+macro_rules! impl_xoroshiro_u64_inv {
+    ($self:expr) => {
+        let t = $self.s1.rotate_right(37);
+        $self.s0 = ($self.s0 ^ t ^ (t << 16)).rotate_right(24);
+        $self.s1 = t ^ $self.s0;
+    }
+}
+
+/// Implement the xoroshiro iteration used by the ++ scrambler.
+macro_rules! impl_xoroshiro_u64_plusplus {
+    ($self:expr) => {
+        $self.s1 ^= $self.s0;
+        $self.s0 = $self.s0.rotate_left(49) ^ $self.s1 ^ ($self.s1 << 21);
+        $self.s1 = $self.s1.rotate_left(28);
+    }
+}
+
+/// Implement the inverse of the xoroshiro iteration used by the ++ scrambler.
+macro_rules! impl_xoroshiro_u64_plusplus_inv {
+    ($self:expr) => {
+        let t = $self.s1.rotate_right(28);
+        $self.s0 = ($self.s0 ^ t ^ (t << 21)).rotate_right(49);
+        $self.s1 = t ^ $self.s0;
+    }
+}
+
 /// Implement the xoshiro iteration for `u32` output.
 macro_rules! impl_xoshiro_u32 {
     ($self:expr) => {
@@ -155,6 +660,21 @@ macro_rules! impl_xoshiro_u32 {
     }
 }
 
+/// Implement the inverse of the xoshiro iteration for `u32` output.
+macro_rules! impl_xoshiro_u32_inv {
+    ($self:expr) => {
+        let s3 = $self.s[3].rotate_right(11);
+        $self.s[0] ^= s3;
+        let u = $self.s[1] ^ $self.s[0];
+        let v = $self.s[2] ^ $self.s[0];
+        // Invert `s1 ^ (s1 << 9)`.
+        let w = u ^ v;
+        $self.s[1] = w ^ (w << 9) ^ (w << 18) ^ (w << 27);
+        $self.s[2] = u ^ $self.s[1];
+        $self.s[3] = s3 ^ $self.s[1];
+    }
+}
+
 /// Implement the xoshiro iteration for `u64` output.
 macro_rules! impl_xoshiro_u64 {
     ($self:expr) => {
@@ -171,6 +691,21 @@ macro_rules! impl_xoshiro_u64 {
     }
 }
 
+/// Implement the inverse of the xoshiro iteration for `u64` output.
+macro_rules! impl_xoshiro_u64_inv {
+    ($self:expr) => {
+        let s3 = $self.s[3].rotate_right(45);
+        $self.s[0] ^= s3;
+        let u = $self.s[1] ^ $self.s[0];
+        let v = $self.s[2] ^ $self.s[0];
+        // Invert `s1 ^ (s1 << 17)`.
+        let w = u ^ v;
+        $self.s[1] = w ^ (w << 17) ^ (w << 34) ^ (w << 51);
+        $self.s[2] = u ^ $self.s[1];
+        $self.s[3] = s3 ^ $self.s[1];
+    }
+}
+
 /// Implement the large-state xoshiro iteration.
 macro_rules! impl_xoshiro_large {
     ($self:expr) => {
@@ -191,15 +726,759 @@ macro_rules! impl_xoshiro_large {
     }
 }
 
-/// Map an all-zero seed to a different one.
+/// Implement the inverse of the large-state xoshiro iteration.
+macro_rules! impl_xoshiro_large_inv {
+    ($self:expr) => {
+        let s7 = $self.s[7].rotate_right(21);
+
+        $self.s[1] ^= $self.s[2];
+        $self.s[5] ^= $self.s[1];
+        $self.s[4] ^= $self.s[5] ^ $self.s[1];
+        $self.s[3] ^= $self.s[4];
+        $self.s[7] = s7 ^ $self.s[3];
+        $self.s[6] ^= s7 ^ ($self.s[1] << 11);
+        $self.s[0] ^= $self.s[6];
+        $self.s[2] ^= $self.s[0];
+    }
+}
+
+/// Implement the large-state xoroshiro iteration.
+macro_rules! impl_xoroshiro_large {
+    ($self:expr) => {
+        let q = $self.p;
+        $self.p = ($self.p + 1) & 15;
+        let s0 = $self.s[$self.p];
+        let s15 = $self.s[q] ^ s0;
+        $self.s[q] = s0.rotate_left(25) ^ s15 ^ (s15 << 27);
+        $self.s[$self.p] = s15.rotate_left(36);
+    }
+}
+
+/// Implement the inverse of the large-state xoroshiro iteration.
+macro_rules! impl_xoroshiro_large_inv {
+    ($self:expr) => {
+        let q = ($self.p + 15) & 15;
+        let s15 = $self.s[$self.p].rotate_right(36);
+        let s0 = ($self.s[q] ^ s15 ^ (s15 << 27)).rotate_right(25);
+        $self.s[$self.p] = s0;
+        $self.s[q] = s15 ^ s0;
+        $self.p = q;
+    }
+}
+
+/// Implement `Deserialize` for an RNG, rejecting states that fail the given
+/// validity check.
+macro_rules! impl_deserialize {
+    ($Self:ident { $($field:ident: $ty:ty),+ }, |$state:ident| $is_valid:expr) => {
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $Self {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where D: ::serde::Deserializer<'de>
+            {
+                // Mirrors the serialized layout of the RNG, so the state can be
+                // validated before it is used.
+                #[derive(::serde::Deserialize)]
+                struct $Self {
+                    $($field: $ty),+
+                }
+
+                let $state = $Self::deserialize(deserializer)?;
+                if !$is_valid {
+                    return Err(::serde::de::Error::custom(
+                        concat!("invalid state for ", stringify!($Self))));
+                }
+                Ok(Self { $($field: $state.$field),+ })
+            }
+        }
+    }
+}
+
+/// Implement `PartialEq`, `Eq` and `Hash` for an RNG with a circular buffer.
+///
+/// Generators in the same logical state may differ in the position of the
+/// buffer, so the rotated state words are compared instead of the fields.
+macro_rules! impl_state_eq {
+    ($Self:ident) => {
+        impl PartialEq for $Self {
+            fn eq(&self, other: &$Self) -> bool {
+                self.state() == other.state()
+            }
+        }
+
+        impl Eq for $Self {}
+
+        impl ::core::hash::Hash for $Self {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                self.state().hash(state);
+            }
+        }
+    }
+}
+
+/// Implement `Serialize` and `Deserialize` for a seed wrapping a large byte
+/// array, using the same layout as for smaller arrays.
+macro_rules! impl_seed_serde {
+    ($Seed:ident, $n:expr) => {
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $Seed {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where S: ::serde::Serializer
+            {
+                use serde::ser::SerializeTuple;
+                let mut tuple = serializer.serialize_tuple($n)?;
+                for byte in self.iter() {
+                    tuple.serialize_element(byte)?;
+                }
+                tuple.end()
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $Seed {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where D: ::serde::Deserializer<'de>
+            {
+                struct SeedVisitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for SeedVisitor {
+                    type Value = $Seed;
+
+                    fn expecting(&self, f: &mut ::core::fmt::Formatter)
+                        -> ::core::fmt::Result
+                    {
+                        write!(f, "an array of {} bytes", $n)
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> Result<$Seed, A::Error>
+                        where A: ::serde::de::SeqAccess<'de>
+                    {
+                        let mut seed = $Seed::default();
+                        for (i, byte) in seed.0.iter_mut().enumerate() {
+                            *byte = seq.next_element()?.ok_or_else(||
+                                ::serde::de::Error::invalid_length(i, &self))?;
+                        }
+                        Ok(seed)
+                    }
+                }
+
+                deserializer.deserialize_tuple($n, SeedVisitor)
+            }
+        }
+    }
+}
+
+/// Implement writing the state of an RNG as a checkpoint.
+#[cfg(feature = "std")]
+macro_rules! write_checkpoint {
+    (s, $self:expr, $writer:expr, $algorithm:expr, [$ty:ty; $n:expr]) => { {
+        let mut words = [0; 8];
+        for (w, &x) in words.iter_mut().zip($self.s.iter()) {
+            *w = u64::from(x);
+        }
+        ::checkpoint::write($writer, $algorithm, ::core::mem::size_of::<$ty>(),
+                            &words[..$self.s.len()])
+    } };
+    (s0 s1, $self:expr, $writer:expr, $algorithm:expr, $ty:ty) => {
+        ::checkpoint::write($writer, $algorithm, ::core::mem::size_of::<$ty>(),
+                            &[u64::from($self.s0), u64::from($self.s1)])
+    };
+    (s p, $self:expr, $writer:expr, $algorithm:expr, $ty:ty) => { {
+        // The circular buffer is written starting at `p`, so that it can be
+        // restored with `p = 0`.
+        let mut words = [0; 16];
+        for (i, w) in words.iter_mut().enumerate() {
+            *w = $self.s[(i + $self.p) & 15];
+        }
+        ::checkpoint::write($writer, $algorithm, 8, &words)
+    } };
+    (x, $self:expr, $writer:expr, $algorithm:expr, $ty:ty) => {
+        ::checkpoint::write($writer, $algorithm, 8, &[$self.x])
+    };
+}
+
+/// Implement restoring an RNG from a checkpoint, rejecting the all-zero state.
+#[cfg(feature = "std")]
+macro_rules! read_checkpoint {
+    (s, $Self:ident, $reader:expr, $algorithm:expr, [$ty:ty; $n:expr]) => { {
+        let mut words = [0; $n];
+        ::checkpoint::read($reader, $algorithm, ::core::mem::size_of::<$ty>(),
+                           &mut words)?;
+        let mut s = [0; $n];
+        for (x, &w) in s.iter_mut().zip(words.iter()) {
+            *x = w as $ty;
+        }
+        if s.iter().all(|&x| x == 0) {
+            return Err(::checkpoint::invalid(
+                concat!("invalid state for ", stringify!($Self))));
+        }
+        Ok($Self { s })
+    } };
+    (s0 s1, $Self:ident, $reader:expr, $algorithm:expr, $ty:ty) => { {
+        let mut words = [0; 2];
+        ::checkpoint::read($reader, $algorithm, ::core::mem::size_of::<$ty>(),
+                           &mut words)?;
+        if words == [0, 0] {
+            return Err(::checkpoint::invalid(
+                concat!("invalid state for ", stringify!($Self))));
+        }
+        Ok($Self { s0: words[0] as $ty, s1: words[1] as $ty })
+    } };
+    (s p, $Self:ident, $reader:expr, $algorithm:expr, $ty:ty) => { {
+        let mut s = [0; 16];
+        ::checkpoint::read($reader, $algorithm, 8, &mut s)?;
+        if s.iter().all(|&x| x == 0) {
+            return Err(::checkpoint::invalid(
+                concat!("invalid state for ", stringify!($Self))));
+        }
+        Ok($Self { s, p: 0 })
+    } };
+    (x, $Self:ident, $reader:expr, $algorithm:expr, $ty:ty) => { {
+        let mut x = [0; 1];
+        ::checkpoint::read($reader, $algorithm, 8, &mut x)?;
+        Ok($Self { x: x[0] })
+    } };
+}
+
+/// Implement writing and restoring checkpoints of an RNG, given the name of
+/// its `next_*` method, the algorithm identifier, and the state fields and
+/// the type of the state as for `read_checkpoint`.
+macro_rules! impl_checkpoint {
+    (@invalid x) => { "." };
+    (@invalid $($state:ident)+) => { ", or if the state is all zero." };
+    ($Rng:ident, $next:ident, $algorithm:expr, $($state:ident)+, $ty:tt) => {
+        /// Write the state as a binary checkpoint.
+        ///
+        /// The checkpoint identifies the algorithm, so restoring it into a
+        /// different generator fails. See the [crate
+        /// documentation](index.html#checkpoints) for the format.
+        ///
+        #[doc = concat!("```
+# extern crate rand;
+# extern crate xoshiro;
+# fn main() {
+use rand::RngCore;
+use xoshiro::", stringify!($Rng), ";
+
+let mut rng1 = ", stringify!($Rng), "::from_seed_u64(0);
+let mut checkpoint = Vec::new();
+rng1.write_checkpoint(&mut checkpoint).unwrap();
+let mut rng2 =
+    ", stringify!($Rng), "::read_checkpoint(&checkpoint[..]).unwrap();
+assert_eq!(rng1.", stringify!($next), "(), rng2.", stringify!($next), "());
+# }
+```")]
+        #[cfg(feature = "std")]
+        pub fn write_checkpoint<W: ::std::io::Write>(&self, writer: W)
+            -> ::std::io::Result<()>
+        {
+            write_checkpoint!($($state)+, self, writer, $algorithm, $ty)
+        }
+
+        #[doc = concat!("Restore a `", stringify!($Rng), "` from a binary \
+                         checkpoint written by `write_checkpoint`.")]
+        ///
+        #[doc = concat!("Fails with `io::ErrorKind::InvalidData` if the \
+                         checkpoint was written by a different generator or \
+                         is corrupted", impl_checkpoint!(@invalid $($state)+))]
+        #[cfg(feature = "std")]
+        pub fn read_checkpoint<R: ::std::io::Read>(reader: R)
+            -> ::std::io::Result<$Rng>
+        {
+            read_checkpoint!($($state)+, $Rng, reader, $algorithm, $ty)
+        }
+    };
+}
+
+/// Check whether all state words are zero, in a way that can be used in
+/// `const fn`.
+macro_rules! const_is_zero {
+    ($s:expr) => { {
+        let mut zero = true;
+        let mut i = 0;
+        while i < $s.len() {
+            zero &= $s[i] == 0;
+            i += 1;
+        }
+        zero
+    } }
+}
+
+/// Map an all-zero seed to the one generated by `SplitMix64` seeded with 0.
 macro_rules! deal_with_zero_seed {
     ($seed:expr, $Self:ident) => {
         if $seed.iter().all(|&x| x == 0) {
-            return $Self::from_seed_u64(0);
+            return from_splitmix!(0);
+        }
+    }
+}
+
+/// Implement the tests shared by all RNGs, in the test module of an RNG
+/// importing its parent module.
+///
+/// The arguments are the type of the words natively generated by the RNG, the
+/// state passed to `new` in a constant, the fields of a state serialized as
+/// the given JSON, and a different generator whose checkpoints are rejected.
+/// Generators from the xoshiro family also get the fields of the all-zero
+/// state, their characteristic polynomial from `gf2` and the base 2
+/// logarithms of the lengths of a jump and a long jump.
+#[cfg(test)]
+macro_rules! test_rng {
+    ($Rng:ident, u64, $($args:tt)*) => {
+        test_rng!(@tests $Rng, u64, next_u64, prev_u64, const_next_u64,
+                  BlockRng64, $($args)*);
+    };
+    ($Rng:ident, u32, $($args:tt)*) => {
+        test_rng!(@tests $Rng, u32, next_u32, prev_u32, const_next_u32,
+                  BlockRng, $($args)*);
+    };
+    (@tests $Rng:ident, $word:ident, $next:ident, $prev:ident,
+     $const_next:ident, $BlockRng:ident, new($new:expr),
+     serde({ $($field:ident: $value:expr),+ }, $json:expr),
+     other($Other:ident)
+     $(, zero({ $($zero_field:ident: $zero_value:expr),+ }),
+     charpoly($charpoly:ident), jumps($jump:expr, $long_jump:expr))?) => {
+        #[test]
+        fn const_fn() {
+            const TABLE: [$word; 3] = {
+                let mut rng = $Rng::new($new);
+                let mut table = [0; 3];
+                let mut i = 0;
+                while i < table.len() {
+                    let (x, next) = rng.$const_next();
+                    table[i] = x;
+                    rng = next;
+                    i += 1;
+                }
+                table
+            };
+            let mut rng = $Rng::new($new);
+            for &x in &TABLE {
+                assert_eq!(x, rng.$next());
+            }
+        }
+
+        #[test]
+        fn from_seed_bytes() {
+            let mut rng1 = $Rng::from_str_seed("experiment 1");
+            let mut rng2 = $Rng::from_seed_bytes(b"experiment 1");
+            let mut rng3 = $Rng::from_str_seed("experiment 2");
+            let x = rng1.$next();
+            assert_eq!(x, rng2.$next());
+            assert!(x != rng3.$next());
+        }
+
+        #[test]
+        fn from_seed_u128() {
+            let mut rng1 = $Rng::from_seed_u128(1);
+            let mut rng2 = $Rng::from_seed_u128(1 | 1 << 64);
+            let seq = ::SeedSequence::new(&[1, 0]);
+            let mut rng3 = $Rng::from_seed_sequence(&seq);
+            let x = rng1.$next();
+            assert!(x != rng2.$next());
+            assert_eq!(x, rng3.$next());
+        }
+
+        #[test]
+        fn advance() {
+            let mut rng1 = $Rng::from_seed_u64(0);
+            let mut rng2 = rng1.clone();
+            for &n in &[0, 1, 2, 15, 16, 17, 63, 64, 65, 1000] {
+                for _ in 0..n {
+                    rng1.$next();
+                }
+                rng2.advance(n);
+                assert_eq!(rng1, rng2);
+                assert_eq!(rng1.$next(), rng2.$next());
+            }
+        }
+
+        #[test]
+        fn fill() {
+            let mut rng1 = $Rng::from_seed_u64(0);
+            let mut rng2 = rng1.clone();
+            let mut words = [0; 11];
+            rng1.fill_u64(&mut words);
+            for &w in &words {
+                assert_eq!(w, rng2.next_u64());
+            }
+            let mut words = [0; 11];
+            rng1.fill_u32(&mut words);
+            for &w in &words {
+                assert_eq!(w, rng2.next_u32());
+            }
+            let mut floats = [0.; 11];
+            rng1.fill_f64(&mut floats);
+            for &x in &floats {
+                assert_eq!(x, rng2.next_f64());
+            }
+            let mut floats = [0.; 11];
+            rng1.fill_f32(&mut floats);
+            for &x in &floats {
+                assert_eq!(x, rng2.next_f32());
+            }
+            assert_eq!(rng1, rng2);
+        }
+
+        #[test]
+        fn block_rng() {
+            use rand_core::block::$BlockRng;
+
+            let mut rng1 = $Rng::from_seed_u64(0);
+            let mut rng2 = $BlockRng::new(rng1.clone());
+            for _ in 0..100 {
+                assert_eq!(rng1.$next(), rng2.$next());
+            }
+        }
+
+        #[test]
+        fn prev() {
+            let mut rng1 = $Rng::from_seed_u64(0);
+            let mut rng2 = rng1.clone();
+            let mut expected = [0; 40];
+            for e in expected.iter_mut() {
+                *e = rng1.next_u64();
+            }
+            for &e in expected.iter().rev() {
+                assert_eq!(rng1.prev_u64(), e);
+            }
+            let e = rng1.next_u32();
+            assert_eq!(rng1.prev_u32(), e);
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+
+        #[test]
+        fn retreat() {
+            let mut rng1 = $Rng::from_seed_u64(0);
+            let mut rng2 = rng1.clone();
+            for &n in &[0, 1, 2, 63, 64, 65, 1000, 1 << 100] {
+                rng2.advance(n);
+                rng2.retreat(n);
+                assert_eq!(rng1.$next(), rng2.$next());
+            }
+            for _ in 0..100 {
+                rng1.$prev();
+            }
+            rng2.retreat(100);
+            assert_eq!(rng1.$next(), rng2.$next());
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde() {
+            let mut rng1 = $Rng { $($field: $value),+ };
+            let json = ::serde_json::to_string(&rng1).unwrap();
+            assert_eq!(json, $json);
+            let mut rng2: $Rng = ::serde_json::from_str(&json).unwrap();
+            assert_eq!(rng1.$next(), rng2.$next());
+            $(
+            let zero = $Rng { $($zero_field: $zero_value),+ };
+            let json = ::serde_json::to_string(&zero).unwrap();
+            assert!(::serde_json::from_str::<$Rng>(&json).is_err());
+            )?
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn checkpoint() {
+            let mut rng1 = $Rng::from_seed_u64(0);
+            for _ in 0..3 {
+                rng1.$next();
+            }
+            let mut buf = Vec::new();
+            rng1.write_checkpoint(&mut buf).unwrap();
+            let mut rng2 = $Rng::read_checkpoint(&buf[..]).unwrap();
+            for _ in 0..20 {
+                assert_eq!(rng1.$next(), rng2.$next());
+            }
+            assert!(::$Other::read_checkpoint(&buf[..]).is_err());
+            $(
+            let mut buf = Vec::new();
+            let zero = $Rng { $($zero_field: $zero_value),+ };
+            zero.write_checkpoint(&mut buf).unwrap();
+            assert!($Rng::read_checkpoint(&buf[..]).is_err());
+            )?
         }
+
+        $(
+        #[test]
+        fn state() {
+            let mut rng1 = $Rng::from_seed_u64(0);
+            for _ in 0..3 {
+                rng1.$next();
+            }
+            let mut rng2 = $Rng::from_state(rng1.state()).unwrap();
+            assert_eq!(rng1, rng2);
+            for _ in 0..20 {
+                assert_eq!(rng1.$next(), rng2.$next());
+            }
+            assert_eq!($Rng::from_state(Default::default()).unwrap_err(),
+                       ::ZeroStateError);
+        }
+
+        #[test]
+        fn zero_seed() {
+            assert_eq!($Rng::try_from_seed(Default::default()).unwrap_err(),
+                       ::ZeroStateError);
+
+            let mut seed = <$Rng as SeedableRng>::Seed::default();
+            ::SplitMix64::from_seed_u64(0).fill_bytes(seed.as_mut());
+            let mut rng1 = $Rng::from_seed(Default::default());
+            let mut rng2 = $Rng::from_seed_u64(0);
+            let mut rng3 = $Rng::try_from_seed(seed).unwrap();
+            for _ in 0..10 {
+                let x = rng1.next_u64();
+                assert_eq!(x, rng2.next_u64());
+                assert_eq!(x, rng3.next_u64());
+            }
+        }
+
+        #[test]
+        fn jump_polynomials() {
+            // Derive the characteristic polynomial from the lowest bit of the
+            // first state word.
+            let mut rng = $Rng::from_seed_u64(0);
+            let mut p = [0; ::gf2::$charpoly.len()];
+            let l = ::gf2::charpoly(|| {
+                rng.$next();
+                rng.state()[0] & 1 != 0
+            }, &mut p);
+            assert_eq!(l, 64 * p.len());
+            assert_eq!(p, ::gf2::$charpoly);
+
+            let mut rng1 = $Rng::from_seed_u64(0);
+            let mut rng2 = rng1.clone();
+            rng1.jump();
+            rng2.advance_pow2($jump);
+            assert_eq!(rng1, rng2);
+            assert_eq!(rng1.$next(), rng2.$next());
+
+            // Jumps fitting into `advance` agree with it.
+            if let Some(n) = 1u128.checked_shl($jump - 1) {
+                let mut rng1 = $Rng::from_seed_u64(0);
+                let mut rng2 = rng1.clone();
+                rng1.jump();
+                rng2.advance(n);
+                rng2.advance(n);
+                assert_eq!(rng1, rng2);
+            }
+
+            let mut rng1 = $Rng::from_seed_u64(0);
+            let mut rng2 = rng1.clone();
+            rng1.long_jump();
+            rng2.advance_pow2($long_jump);
+            assert_eq!(rng1, rng2);
+            assert_eq!(rng1.$next(), rng2.$next());
+        }
+
+        #[test]
+        fn advance_pow2() {
+            // Jumping by small powers of two agrees with stepping.
+            for e in 0..12 {
+                let mut rng1 = $Rng::from_seed_u64(0);
+                let mut rng2 = rng1.clone();
+                for _ in 0..1 << e {
+                    rng1.$next();
+                }
+                rng2.advance_pow2(e);
+                assert_eq!(rng1, rng2);
+                assert_eq!(rng1.$next(), rng2.$next());
+            }
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn streams() {
+            let rng = $Rng::from_seed_u64(0);
+            let mut expected = rng.clone();
+            for mut stream in rng.streams(3) {
+                assert_eq!(stream.$next(), expected.clone().$next());
+                expected.jump();
+            }
+        }
+        )?
+    };
+}
+
+/// The error returned when trying to create a generator with an all-zero
+/// state or seed.
+///
+/// The xoshiro and xoroshiro generators only produce zeros in this state, so
+/// it is forbidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZeroStateError;
+
+impl ::core::fmt::Display for ZeroStateError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        f.write_str("the state of the generator is all zero")
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for ZeroStateError {}
+
+/// The error returned when parsing a seed from a hex string fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseSeedError {
+    /// The string does not consist of two hex digits per byte of the seed.
+    InvalidLength,
+    /// The string contains a character that is not a hex digit.
+    InvalidDigit,
+}
+
+impl ::core::fmt::Display for ParseSeedError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        f.write_str(match *self {
+            ParseSeedError::InvalidLength => "invalid length of hex seed",
+            ParseSeedError::InvalidDigit => "invalid digit in hex seed",
+        })
     }
 }
 
+#[cfg(feature = "std")]
+impl ::std::error::Error for ParseSeedError {}
+
+/// Parse the hex string `s` into `bytes`, expecting two digits per byte.
+fn parse_hex(s: &str, bytes: &mut [u8]) -> Result<(), ParseSeedError> {
+    let s = s.as_bytes();
+    if s.len() != 2 * bytes.len() {
+        return Err(ParseSeedError::InvalidLength);
+    }
+    let digit = |c: u8| {
+        (c as char).to_digit(16).ok_or(ParseSeedError::InvalidDigit)
+    };
+    for (b, pair) in bytes.iter_mut().zip(s.chunks(2)) {
+        *b = (digit(pair[0])? << 4 | digit(pair[1])?) as u8;
+    }
+    Ok(())
+}
+
+/// Implement the methods, conversions and the hex encoding shared by all
+/// seeds.
+macro_rules! impl_seed {
+    ($Seed:ident, $n:expr, $words:expr) => {
+        impl $Seed {
+            /// Return an iterator over the seed.
+            pub fn iter(&self) -> ::core::slice::Iter<'_, u8> {
+                self.0.iter()
+            }
+
+            /// Return whether all bytes of the seed are zero.
+            ///
+            /// All bytes are inspected, so the time taken does not depend on
+            /// the position of the first nonzero byte.
+            pub fn is_zero(&self) -> bool {
+                self.0.iter().fold(0, |acc, &b| acc | b) == 0
+            }
+        }
+
+        impl From<[u8; $n]> for $Seed {
+            fn from(bytes: [u8; $n]) -> $Seed {
+                $Seed(bytes)
+            }
+        }
+
+        impl From<$Seed> for [u8; $n] {
+            fn from(seed: $Seed) -> [u8; $n] {
+                seed.0
+            }
+        }
+
+        /// The words are stored in little-endian byte order, so seeding a
+        /// generator with the result uses them as its state.
+        impl From<[u64; $words]> for $Seed {
+            fn from(words: [u64; $words]) -> $Seed {
+                use byteorder::{ByteOrder, LittleEndian};
+                let mut seed = $Seed::default();
+                LittleEndian::write_u64_into(&words, &mut seed.0);
+                seed
+            }
+        }
+
+        impl AsRef<[u8]> for $Seed {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl AsMut<[u8]> for $Seed {
+            fn as_mut(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+        }
+
+        /// Format the seed as lowercase hex digits, two per byte.
+        impl ::core::fmt::Display for $Seed {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter)
+                -> ::core::fmt::Result
+            {
+                for b in self.iter() {
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+        }
+
+        /// Parse the seed from hex digits, two per byte, as written by
+        /// `Display`. Upper case digits are accepted as well.
+        impl ::core::str::FromStr for $Seed {
+            type Err = ParseSeedError;
+
+            fn from_str(s: &str) -> Result<$Seed, ParseSeedError> {
+                let mut seed = $Seed::default();
+                parse_hex(s, &mut seed.0)?;
+                Ok(seed)
+            }
+        }
+    }
+}
+
+/// 64-bit seed for a generator.
+///
+/// It converts from and into `[u8; 8]`, the seed of the xoroshiro64
+/// generators.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Seed64(pub [u8; 8]);
+
+impl_seed!(Seed64, 8, 1);
+
+/// 128-bit seed for a generator.
+///
+/// It converts from and into `[u8; 16]`, the seed of the xoshiro128 and
+/// xoroshiro128 generators.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Seed128(pub [u8; 16]);
+
+impl_seed!(Seed128, 16, 2);
+
+/// 256-bit seed for a generator.
+///
+/// It converts from and into `[u8; 32]`, the seed of the xoshiro256
+/// generators. Seeds can be parsed from and formatted as hex strings, so they
+/// can be passed on the command line and logged for reproducibility:
+///
+/// ```
+/// # extern crate rand_core;
+/// # extern crate xoshiro;
+/// # fn main() {
+/// use rand_core::SeedableRng;
+/// use xoshiro::{Seed256, Xoshiro256StarStar};
+///
+/// let hex = "000102030405060708090a0b0c0d0e0f\
+///            101112131415161718191a1b1c1d1e1f";
+/// let seed: Seed256 = hex.parse().unwrap();
+/// assert_eq!(seed.to_string(), hex);
+/// let rng = Xoshiro256StarStar::from_seed(seed.into());
+/// # let _ = rng;
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Seed256(pub [u8; 32]);
+
+impl_seed!(Seed256, 32, 4);
+
 /// 512-bit seed for a generator.
 ///
 /// This wrapper is necessary, because some traits required for a seed are not
@@ -207,16 +1486,25 @@ macro_rules! deal_with_zero_seed {
 #[derive(Clone)]
 pub struct Seed512(pub [u8; 64]);
 
-impl Seed512 {
-    /// Return an iterator over the seed.
-    pub fn iter(&self) -> ::std::slice::Iter<u8> {
-        self.0.iter()
+impl_seed!(Seed512, 64, 8);
+
+impl ::core::fmt::Debug for Seed512 {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        self.0[..].fmt(f)
+    }
+}
+
+impl PartialEq for Seed512 {
+    fn eq(&self, other: &Seed512) -> bool {
+        self.0[..] == other.0[..]
     }
 }
 
-impl ::std::fmt::Debug for Seed512 {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-        self.0[..].fmt(f)
+impl Eq for Seed512 {}
+
+impl ::core::hash::Hash for Seed512 {
+    fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+        self.0[..].hash(state);
     }
 }
 
@@ -226,9 +1514,192 @@ impl Default for Seed512 {
     }
 }
 
-impl AsMut<[u8]> for Seed512 {
-    fn as_mut(&mut self) -> &mut [u8] {
-        &mut self.0
+impl_seed_serde!(Seed512, 64);
+
+
+/// 1024-bit seed for a generator.
+///
+/// This wrapper is necessary, because some traits required for a seed are not
+/// implemented on large arrays.
+#[derive(Clone)]
+pub struct Seed1024(pub [u8; 128]);
+
+impl_seed!(Seed1024, 128, 16);
+
+impl ::core::fmt::Debug for Seed1024 {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        self.0[..].fmt(f)
+    }
+}
+
+impl PartialEq for Seed1024 {
+    fn eq(&self, other: &Seed1024) -> bool {
+        self.0[..] == other.0[..]
     }
 }
 
+impl Eq for Seed1024 {}
+
+impl ::core::hash::Hash for Seed1024 {
+    fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+        self.0[..].hash(state);
+    }
+}
+
+impl Default for Seed1024 {
+    fn default() -> Seed1024 {
+        Seed1024([0; 128])
+    }
+}
+
+impl_seed_serde!(Seed1024, 128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    fn hash<T: ::core::hash::Hash>(x: &T) -> u64 {
+        use std::hash::Hasher;
+        let mut hasher = ::std::collections::hash_map::DefaultHasher::new();
+        x.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn seed_eq() {
+        let mut seed = Seed512::default();
+        assert_eq!(seed, Seed512([0; 64]));
+        seed.0[63] = 1;
+        assert!(seed != Seed512::default());
+        assert_eq!(Seed1024([7; 128]), Seed1024([7; 128]));
+        assert!(Seed1024([7; 128]) != Seed1024::default());
+    }
+
+    #[test]
+    fn seed_words() {
+        use rand_core::SeedableRng;
+
+        let seed = Seed64::from([0x0123456789abcdef]);
+        assert_eq!(seed.0, [0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01]);
+
+        // Seeding with words uses them as the state.
+        let s = [1, 2, 3, 4];
+        let rng = ::Xoshiro256StarStar::from_seed(Seed256::from(s).into());
+        assert_eq!(rng.state(), s);
+        let s = [1, 2, 3, 4, 5, 6, 7, 8];
+        let rng = ::Xoshiro512StarStar::from_seed(Seed512::from(s));
+        assert_eq!(rng.state(), s);
+        let rng = ::Xoroshiro128StarStar::from_seed(Seed128::from([5, 6]).0);
+        assert_eq!(rng.state(), [5, 6]);
+    }
+
+    #[test]
+    fn seed_is_zero() {
+        assert!(Seed64::default().is_zero());
+        assert!(Seed1024::default().is_zero());
+        let mut seed = Seed128::default();
+        seed.as_mut()[15] = 0x80;
+        assert!(!seed.is_zero());
+        assert_eq!(seed.as_ref()[15], 0x80);
+    }
+
+    #[test]
+    fn seed_hex() {
+        let seed = Seed64([0x00, 0x01, 0x7f, 0x80, 0xab, 0xcd, 0xef, 0xff]);
+        let mut buf = [0u8; 16];
+        {
+            use core::fmt::Write;
+            struct Buf<'a>(&'a mut [u8], usize);
+            impl<'a> Write for Buf<'a> {
+                fn write_str(&mut self, s: &str) -> ::core::fmt::Result {
+                    self.0[self.1..self.1 + s.len()]
+                        .copy_from_slice(s.as_bytes());
+                    self.1 += s.len();
+                    Ok(())
+                }
+            }
+            write!(Buf(&mut buf, 0), "{}", seed).unwrap();
+        }
+        assert_eq!(&buf, b"00017f80abcdefff");
+        assert_eq!("00017f80abcdefff".parse(), Ok(seed));
+        assert_eq!("00017F80ABCDEFFF".parse(), Ok(seed));
+
+        assert_eq!("00017f80abcdef".parse::<Seed64>(),
+                   Err(ParseSeedError::InvalidLength));
+        assert_eq!("00017f80abcdefff00".parse::<Seed64>(),
+                   Err(ParseSeedError::InvalidLength));
+        assert_eq!("00017f80abcdefgf".parse::<Seed64>(),
+                   Err(ParseSeedError::InvalidDigit));
+        assert_eq!("+0017f80abcdefff".parse::<Seed64>(),
+                   Err(ParseSeedError::InvalidDigit));
+        assert_eq!("\u{e9}017f80abcdefff".parse::<Seed64>(),
+                   Err(ParseSeedError::InvalidDigit));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn seed_hex_round_trip() {
+        let mut seed = Seed1024::default();
+        for (i, b) in seed.0.iter_mut().enumerate() {
+            *b = (i * 37) as u8;
+        }
+        let hex = seed.to_string();
+        assert_eq!(hex.len(), 256);
+        assert_eq!(hex.parse(), Ok(seed));
+        let seed = Seed512([0xa5; 64]);
+        assert_eq!(seed.to_string().parse(), Ok(seed));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn eq_hash() {
+        use rand_core::RngCore;
+
+        assert_eq!(hash(&Seed512([3; 64])), hash(&Seed512([3; 64])));
+        assert_eq!(hash(&Seed1024([3; 128])), hash(&Seed1024([3; 128])));
+
+        // Generators in the same state are equal and have the same hash, even
+        // if the position in the circular buffer differs.
+        let mut rng1 = ::Xoroshiro1024StarStar::from_seed_u64(0);
+        for _ in 0..5 {
+            rng1.next_u64();
+        }
+        let rng2 = ::Xoroshiro1024StarStar::from_state(rng1.state()).unwrap();
+        assert_eq!(rng1, rng2);
+        assert_eq!(hash(&rng1), hash(&rng2));
+        let mut rng3 = rng2.clone();
+        rng3.next_u64();
+        assert!(rng2 != rng3);
+
+        let mut rng1 = ::Xoshiro256StarStar::from_seed_u64(0);
+        let rng2 = rng1.clone();
+        assert_eq!(hash(&rng1), hash(&rng2));
+        rng1.next_u64();
+        assert!(rng1 != rng2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let mut seed = Seed512::default();
+        for (i, x) in seed.0.iter_mut().enumerate() {
+            *x = i as u8;
+        }
+        let json = ::serde_json::to_string(&seed).unwrap();
+        let seed2: Seed512 = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(&seed.0[..], &seed2.0[..]);
+        assert!(::serde_json::from_str::<Seed512>("[0, 1, 2]").is_err());
+
+        let seed = Seed1024([7; 128]);
+        let json = ::serde_json::to_string(&seed).unwrap();
+        let seed2: Seed1024 = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(&seed.0[..], &seed2.0[..]);
+
+        // Smaller seeds have the same layout.
+        let seed = Seed64([1, 2, 3, 4, 5, 6, 7, 8]);
+        let json = ::serde_json::to_string(&seed).unwrap();
+        assert_eq!(json, "[1,2,3,4,5,6,7,8]");
+        assert_eq!(::serde_json::from_str::<Seed64>(&json).unwrap(), seed);
+    }
+}
@@ -0,0 +1,310 @@
+//! Conversions of random bits to floating-point numbers in the unit interval.
+//!
+//! The generators pass only their upper bits to these functions, because the
+//! lowest bits of the + scrambler have a low linear complexity.
+
+/// 2^-53, the spacing of `f64` values in [0.5, 1).
+const F64_EPSILON: f64 = 1.0 / (1u64 << 53) as f64;
+
+/// 2^-24, the spacing of `f32` values in [0.5, 1).
+const F32_EPSILON: f32 = 1.0 / (1u32 << 24) as f32;
+
+/// Convert 53 random bits to a float in [0, 1).
+#[inline]
+pub fn f64_closed_open(bits: u64) -> f64 {
+    bits as f64 * F64_EPSILON
+}
+
+/// Convert 53 random bits to a float in (0, 1].
+#[inline]
+pub fn f64_open_closed(bits: u64) -> f64 {
+    (bits + 1) as f64 * F64_EPSILON
+}
+
+/// Convert 53 random bits to a float in (0, 1), using the upper 52 of them.
+#[inline]
+pub fn f64_open(bits: u64) -> f64 {
+    ((bits >> 1) as f64 + 0.5) * (2.0 * F64_EPSILON)
+}
+
+/// Convert random bits to a float in [0, 1), such that every representable
+/// float may be returned.
+///
+/// The 52 lowest bits of `mantissa` are used for the mantissa, and the
+/// exponent is determined by counting the leading zeros of the words
+/// returned by `next`, which are only called as often as necessary. Only the
+/// upper `bits` bits of the words are random, the others have to be zero.
+/// Results below 2^-1022 are rounded down to zero, which happens with
+/// probability 2^-1022.
+#[inline]
+pub fn f64_full<F: FnMut() -> u64>(mantissa: u64, bits: u32, mut next: F)
+    -> f64
+{
+    // The biased exponent of [0.5, 1).
+    let mut exponent = 1022i32;
+    loop {
+        let x = next();
+        if x != 0 {
+            exponent -= x.leading_zeros() as i32;
+            break;
+        }
+        exponent -= bits as i32;
+        if exponent <= 0 {
+            break;
+        }
+    }
+    if exponent <= 0 {
+        return 0.;
+    }
+    let mantissa = mantissa & ((1 << 52) - 1);
+    f64::from_bits((exponent as u64) << 52 | mantissa)
+}
+
+/// Convert 24 random bits to a float in [0, 1).
+#[inline]
+pub fn f32_closed_open(bits: u32) -> f32 {
+    bits as f32 * F32_EPSILON
+}
+
+/// Convert 24 random bits to a float in (0, 1].
+#[inline]
+pub fn f32_open_closed(bits: u32) -> f32 {
+    (bits + 1) as f32 * F32_EPSILON
+}
+
+/// Convert 24 random bits to a float in (0, 1), using the upper 23 of them.
+#[inline]
+pub fn f32_open(bits: u32) -> f32 {
+    ((bits >> 1) as f32 + 0.5) * (2.0 * F32_EPSILON)
+}
+
+/// Convert random bits to a float in [0, 1), such that every representable
+/// float may be returned.
+///
+/// Like `f64_full`, with the 23 lowest bits of `mantissa` used for the
+/// mantissa. Results below 2^-126 are rounded down to zero.
+#[inline]
+pub fn f32_full<F: FnMut() -> u32>(mantissa: u32, bits: u32, mut next: F)
+    -> f32
+{
+    // The biased exponent of [0.5, 1).
+    let mut exponent = 126i32;
+    loop {
+        let x = next();
+        if x != 0 {
+            exponent -= x.leading_zeros() as i32;
+            break;
+        }
+        exponent -= bits as i32;
+        if exponent <= 0 {
+            break;
+        }
+    }
+    if exponent <= 0 {
+        return 0.;
+    }
+    let mantissa = mantissa & ((1 << 23) - 1);
+    f32::from_bits((exponent as u32) << 23 | mantissa)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::{RngCore, Error};
+    use rand_core::impls::{fill_bytes_via_next, next_u64_via_u32};
+
+    /// A generator returning the given words, to check which bits are used.
+    ///
+    /// Like `Xoshiro512Plus`, `next_u32` returns the lowest bits.
+    struct Words64<'a>(&'a [u64]);
+
+    impl<'a> RngCore for Words64<'a> {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let (&x, rest) = self.0.split_first().unwrap();
+            self.0 = rest;
+            x
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            fill_bytes_via_next(self, dest);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl<'a> Words64<'a> {
+        impl_floats!(u64);
+    }
+
+    /// Like `Words64`, for a 32-bit generator.
+    struct Words32<'a>(&'a [u32]);
+
+    impl<'a> RngCore for Words32<'a> {
+        fn next_u32(&mut self) -> u32 {
+            let (&x, rest) = self.0.split_first().unwrap();
+            self.0 = rest;
+            x
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            next_u64_via_u32(self)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            fill_bytes_via_next(self, dest);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl<'a> Words32<'a> {
+        impl_floats!(u32);
+    }
+
+    #[test]
+    fn bounds() {
+        let max53 = (1 << 53) - 1;
+        assert_eq!(f64_closed_open(0), 0.);
+        assert_eq!(f64_closed_open(max53), 1. - F64_EPSILON);
+        assert_eq!(f64_open_closed(0), F64_EPSILON);
+        assert_eq!(f64_open_closed(max53), 1.);
+        assert_eq!(f64_open(0), F64_EPSILON);
+        assert_eq!(f64_open(max53), 1. - F64_EPSILON);
+
+        let max24 = (1 << 24) - 1;
+        assert_eq!(f32_closed_open(0), 0.);
+        assert_eq!(f32_closed_open(max24), 1. - F32_EPSILON);
+        assert_eq!(f32_open_closed(0), F32_EPSILON);
+        assert_eq!(f32_open_closed(max24), 1.);
+        assert_eq!(f32_open(0), F32_EPSILON);
+        assert_eq!(f32_open(max24), 1. - F32_EPSILON);
+    }
+
+    #[test]
+    fn full() {
+        let mut words = [1u64 << 63, 0, 0].iter().cloned();
+        assert_eq!(f64_full(0, 64, || words.next().unwrap()), 0.5);
+        assert_eq!(f64_full(!0, 64, || 1 << 63), 1. - F64_EPSILON);
+        let mut words = [0u64, 0, 1 << 62].iter().cloned();
+        assert_eq!(f64_full(0, 64, || words.next().unwrap()), 2f64.powi(-130));
+        let mut words = [0u64, 0, 1 << 62].iter().cloned();
+        assert_eq!(f64_full(0, 53, || words.next().unwrap()), 2f64.powi(-108));
+        assert_eq!(f64_full(1, 64, || 1),
+                   2f64.powi(-64) * (1. + 2f64.powi(-52)));
+        assert_eq!(f64_full(!0, 64, || 0), 0.);
+
+        let mut words = [1u32 << 31, 0].iter().cloned();
+        assert_eq!(f32_full(0, 32, || words.next().unwrap()), 0.5);
+        assert_eq!(f32_full(!0, 32, || 1 << 31), 1. - F32_EPSILON);
+        let mut words = [0u32, 0, 1 << 30].iter().cloned();
+        assert_eq!(f32_full(0, 32, || words.next().unwrap()), 2f32.powi(-66));
+        let mut words = [0u32, 0, 1 << 30].iter().cloned();
+        assert_eq!(f32_full(0, 24, || words.next().unwrap()), 2f32.powi(-50));
+        assert_eq!(f32_full(!0, 32, || 0), 0.);
+    }
+
+    #[test]
+    fn low_bits_u64() {
+        // The lowest bits never influence the result.
+        let x = 0x0123456789abcdef;
+        for &low in &[0, 0x7ff, 0x555] {
+            let w = [x | low; 16];
+            let v = [x & !0x7ff; 16];
+            let (mut a, mut b) = (Words64(&w), Words64(&v));
+            assert_eq!(a.next_f64(), b.next_f64());
+            assert_eq!(a.next_f64_open_closed(), b.next_f64_open_closed());
+            assert_eq!(a.next_f64_open(), b.next_f64_open());
+            assert_eq!(a.next_f64_full(), b.next_f64_full());
+            assert_eq!(a.next_f32(), b.next_f32());
+            assert_eq!(a.next_f32_open_closed(), b.next_f32_open_closed());
+            assert_eq!(a.next_f32_open(), b.next_f32_open());
+            assert_eq!(a.next_f32_full(), b.next_f32_full());
+        }
+        // Not even if the upper bits are zero.
+        let (w, v) = ([0x7ff; 32], [0; 32]);
+        assert_eq!(Words64(&w).next_f64_full(), Words64(&v).next_f64_full());
+        assert_eq!(Words64(&w).next_f32_full(), Words64(&v).next_f32_full());
+        assert_eq!(Words64(&[!0]).next_f64(), 1. - F64_EPSILON);
+        assert_eq!(Words64(&[!0]).next_f32(), 1. - F32_EPSILON);
+        assert_eq!(Words64(&[1 << 11]).next_f64(), F64_EPSILON);
+        assert_eq!(Words64(&[1 << 40]).next_f32(), F32_EPSILON);
+    }
+
+    #[test]
+    fn low_bits_u32() {
+        let x = 0x89abcdef;
+        for &low in &[0, 0x1f, 0x15] {
+            let w = [x | low; 16];
+            let v = [x & !0x1f; 16];
+            let (mut a, mut b) = (Words32(&w), Words32(&v));
+            assert_eq!(a.next_f64(), b.next_f64());
+            assert_eq!(a.next_f64_open_closed(), b.next_f64_open_closed());
+            assert_eq!(a.next_f64_open(), b.next_f64_open());
+            assert_eq!(a.next_f64_full(), b.next_f64_full());
+            assert_eq!(a.next_f32(), b.next_f32());
+            assert_eq!(a.next_f32_open_closed(), b.next_f32_open_closed());
+            assert_eq!(a.next_f32_open(), b.next_f32_open());
+            assert_eq!(a.next_f32_full(), b.next_f32_full());
+        }
+        let (w, v) = ([0x1f; 64], [0; 64]);
+        assert_eq!(Words32(&w).next_f64_full(), Words32(&v).next_f64_full());
+        assert_eq!(Words32(&w).next_f32_full(), Words32(&v).next_f32_full());
+        assert_eq!(Words32(&[!0, !0]).next_f64(), 1. - F64_EPSILON);
+        assert_eq!(Words32(&[!0]).next_f32(), 1. - F32_EPSILON);
+        assert_eq!(Words32(&[0, 1 << 6]).next_f64(), F64_EPSILON);
+        assert_eq!(Words32(&[1 << 8]).next_f32(), F32_EPSILON);
+    }
+
+    #[test]
+    fn full_upper_bits() {
+        // `Xoshiro512Plus::next_u32` returns the lowest bits of the output,
+        // which are not used for the exponent either.
+        let mut rng = ::Xoshiro512Plus::from_seed_u64(0);
+        let mut upper = rng.clone();
+        for _ in 0..100 {
+            let mantissa = upper.next_u64() >> 12;
+            let x = f64_full(mantissa, 53, || upper.next_u64() >> 11 << 11);
+            assert_eq!(rng.next_f64_full(), x);
+
+            let mantissa = (upper.next_u64() >> 41) as u32;
+            let x = f32_full(mantissa, 24,
+                             || (upper.next_u64() >> 40 << 8) as u32);
+            assert_eq!(rng.next_f32_full(), x);
+        }
+    }
+
+    #[test]
+    fn distribution() {
+        let mut rng = ::Xoshiro256Plus::from_seed_u64(0);
+        let (mut sum, mut small) = (0., 0);
+        for _ in 0..10000 {
+            let x = rng.next_f64_full();
+            assert!((0. ..1.).contains(&x));
+            sum += x;
+            if x < 1. / 16. {
+                small += 1;
+            }
+        }
+        assert!((sum / 10000. - 0.5).abs() < 0.01);
+        assert!(small > 500 && small < 750);
+
+        let mut rng = ::Xoshiro128Plus::from_seed_u64(0);
+        let mut sum = 0.;
+        for _ in 0..10000 {
+            let x = rng.next_f32_open();
+            assert!(x > 0. && x < 1.);
+            sum += x;
+        }
+        assert!((sum / 10000. - 0.5).abs() < 0.01);
+    }
+}
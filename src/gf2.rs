@@ -0,0 +1,318 @@
+//! Arithmetic on polynomials over GF(2), used for jumping ahead.
+//!
+//! A polynomial of degree less than `n` is stored as `n / 64` little-endian
+//! `u64` words, where bit `i` is the coefficient of `x^i`. Products are
+//! reduced modulo a monic polynomial of degree `n`, which is stored the same
+//! way with its leading term `x^n` left implicit.
+
+/// The maximal number of words of a polynomial.
+pub const MAX_WORDS: usize = 16;
+
+/// Characteristic polynomial of the xoroshiro64 linear engine.
+pub const XOROSHIRO64: [u64; 1] = [0x053be9da6e2286c1];
+
+/// Characteristic polynomial of the xoroshiro128 linear engine.
+pub const XOROSHIRO128: [u64; 2] = [0x095b8f76579aa001, 0x0008828e513b43d5];
+
+/// Characteristic polynomial of the xoroshiro128 linear engine used by the ++
+/// scrambler.
+pub const XOROSHIRO128PLUSPLUS: [u64; 2] = [
+    0x8dae70779760b081, 0x0031bcf2f855d6e5,
+];
+
+/// Characteristic polynomial of the xoshiro128 linear engine.
+pub const XOSHIRO128: [u64; 2] = [0x1b489db6de18fc01, 0x00fc65a2006254b1];
+
+/// Characteristic polynomial of the xoshiro256 linear engine.
+pub const XOSHIRO256: [u64; 4] = [
+    0x9d116f2bb0f0f001, 0x0280002bcefd1a5e,
+    0x04b4edcf26259f85, 0x0003c03c3f3ecb19,
+];
+
+/// Characteristic polynomial of the xoshiro512 linear engine.
+pub const XOSHIRO512: [u64; 8] = [
+    0xcf3cff0c00000001, 0x7fdc78d886f00c63, 0xf05e63fca6d7b781,
+    0x7a67058e7bbab6f0, 0xf11eef832e32518f, 0x51ba7c47edc758ad,
+    0x8f2d27268ce4b20b, 0x0000500055d8b77f,
+];
+
+/// Characteristic polynomial of the xoroshiro1024 linear engine.
+pub const XOROSHIRO1024: [u64; 16] = [
+    0x5cfeb8cc48ddb211, 0xb73e379d035a06dd, 0x17d5100a20a0350e,
+    0x7550223f68f98cac, 0x29d373b5c5ed3459, 0x3689b412ef70de48,
+    0xa1d3b6ee079a7cc6, 0x9bf0b669abd100f8, 0x955c84e105f60997,
+    0x6ca140c61889cddd, 0xabaf68c5fc3a0e4a, 0xa46134526b83adc5,
+    0x0710704d05683d63, 0x580d080b44b606a2, 0x008040a0580158a1,
+    0x0000000000800081,
+];
+
+/// Multiply `a` by `x` modulo `p`.
+#[inline]
+fn mul_x(a: &mut [u64], p: &[u64]) {
+    let mut carry = 0;
+    for w in a.iter_mut() {
+        let c = *w >> 63;
+        *w = (*w << 1) | carry;
+        carry = c;
+    }
+    if carry != 0 {
+        for (w, &q) in a.iter_mut().zip(p) {
+            *w ^= q;
+        }
+    }
+}
+
+/// Calculate `a * b` modulo `p` and store it in `out`.
+pub fn mul_mod(a: &[u64], b: &[u64], p: &[u64], out: &mut [u64]) {
+    debug_assert!(a.len() == p.len() && b.len() == p.len());
+    debug_assert!(out.len() == p.len());
+    for w in out.iter_mut() {
+        *w = 0;
+    }
+    // Horner's method, starting with the highest coefficient of `a`.
+    for i in (0..64 * p.len()).rev() {
+        mul_x(out, p);
+        if a[i / 64] & 1 << (i % 64) != 0 {
+            for (w, &v) in out.iter_mut().zip(b) {
+                *w ^= v;
+            }
+        }
+    }
+}
+
+/// Calculate `x^n` modulo `p` and store it in `out`.
+pub fn pow_x(n: u128, p: &[u64], out: &mut [u64]) {
+    debug_assert!(out.len() == p.len() && p.len() <= MAX_WORDS);
+    for w in out.iter_mut() {
+        *w = 0;
+    }
+    out[0] = 1;
+    let mut tmp = [0; MAX_WORDS];
+    let tmp = &mut tmp[..p.len()];
+    for b in (0..128 - n.leading_zeros()).rev() {
+        mul_mod(out, out, p, tmp);
+        out.copy_from_slice(tmp);
+        if n & 1 << b != 0 {
+            mul_x(out, p);
+        }
+    }
+}
+
+/// Calculate `x^-n` modulo `p` and store it in `out`.
+///
+/// `p` has to have a nonzero constant term.
+pub fn pow_x_inv(n: u128, p: &[u64], out: &mut [u64]) {
+    debug_assert!(out.len() == p.len() && p.len() <= MAX_WORDS);
+    debug_assert!(p[0] & 1 != 0);
+    // Writing `p = x^k + x q + 1`, the inverse of `x` is `x^(k - 1) + q`.
+    let mut inv = [0; MAX_WORDS];
+    let inv = &mut inv[..p.len()];
+    for i in 0..p.len() {
+        inv[i] = p[i] >> 1;
+        if i + 1 < p.len() {
+            inv[i] |= p[i + 1] << 63;
+        }
+    }
+    inv[p.len() - 1] |= 1 << 63;
+
+    for w in out.iter_mut() {
+        *w = 0;
+    }
+    out[0] = 1;
+    let mut tmp = [0; MAX_WORDS];
+    let tmp = &mut tmp[..p.len()];
+    for b in (0..128 - n.leading_zeros()).rev() {
+        mul_mod(out, out, p, tmp);
+        out.copy_from_slice(tmp);
+        if n & 1 << b != 0 {
+            mul_mod(out, inv, p, tmp);
+            out.copy_from_slice(tmp);
+        }
+    }
+}
+
+/// Calculate `x^(2^e)` modulo `p` and store it in `out`.
+///
+/// `p` has to be primitive.
+pub fn pow_x_pow2(e: u32, p: &[u64], out: &mut [u64]) {
+    debug_assert!(out.len() == p.len() && p.len() <= MAX_WORDS);
+    // `x^(2^n) = x` modulo a primitive polynomial of degree `n`.
+    let e = e as usize % (64 * p.len());
+    for w in out.iter_mut() {
+        *w = 0;
+    }
+    out[0] = 2;
+    let mut tmp = [0; MAX_WORDS];
+    let tmp = &mut tmp[..p.len()];
+    for _ in 0..e {
+        mul_mod(out, out, p, tmp);
+        out.copy_from_slice(tmp);
+    }
+}
+
+/// Return bit `i` of `a`.
+#[cfg(test)]
+#[inline]
+fn bit(a: &[u64], i: usize) -> u64 {
+    a[i / 64] >> (i % 64) & 1
+}
+
+/// Calculate the characteristic polynomial of a linear recurrence of order
+/// `64 * p.len()` and store it in `p`.
+///
+/// The polynomial is derived with the Berlekamp-Massey algorithm from the bits
+/// returned by `next_bit`, of which twice the order are consumed. The linear
+/// complexity of the bits is returned; if it is less than the order, the bits
+/// do not determine the characteristic polynomial.
+///
+/// This is used to verify the hard-coded polynomials above.
+#[cfg(test)]
+pub fn charpoly<F: FnMut() -> bool>(mut next_bit: F, p: &mut [u64]) -> usize {
+    debug_assert!(p.len() <= MAX_WORDS);
+    let n = 64 * p.len();
+    let mut s = [0u64; 2 * MAX_WORDS];
+    for i in 0..2 * n {
+        if next_bit() {
+            s[i / 64] |= 1 << (i % 64);
+        }
+    }
+    // Connection polynomials, with bit `i` being the coefficient of `x^i`.
+    let mut c = [0u64; MAX_WORDS + 1];
+    c[0] = 1;
+    let mut b = c;
+    let mut l = 0;
+    let mut m = 1;
+    for i in 0..2 * n {
+        let mut d = bit(&s, i);
+        for j in 1..=l {
+            d ^= bit(&c, j) & bit(&s, i - j);
+        }
+        if d == 0 {
+            m += 1;
+            continue;
+        }
+        let t = c;
+        // Terms of a degree larger than the order are dropped; they only
+        // occur if the linear complexity exceeds it.
+        for j in 0..(n + 1).saturating_sub(m) {
+            if bit(&b, j) != 0 {
+                c[(j + m) / 64] ^= 1 << ((j + m) % 64);
+            }
+        }
+        if 2 * l <= i {
+            l = i + 1 - l;
+            b = t;
+            m = 1;
+        } else {
+            m += 1;
+        }
+    }
+    // The characteristic polynomial is the reciprocal of the connection
+    // polynomial.
+    for w in p.iter_mut() {
+        *w = 0;
+    }
+    if l == n {
+        for i in 0..n {
+            p[i / 64] |= bit(&c, n - i) << (i % 64);
+        }
+    }
+    l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pow_x_small() {
+        let mut r = [0; 2];
+        pow_x(0, &XOROSHIRO128, &mut r);
+        assert_eq!(r, [1, 0]);
+        pow_x(100, &XOROSHIRO128, &mut r);
+        assert_eq!(r, [0, 1 << 36]);
+        // x^128 is congruent to the lower terms of the modulus.
+        pow_x(128, &XOROSHIRO128, &mut r);
+        assert_eq!(r, XOROSHIRO128);
+    }
+
+    #[test]
+    fn pow_x_period() {
+        // The characteristic polynomials are primitive, so x has order
+        // 2^64 - 1 for the 64-bit state.
+        let mut r = [0; 1];
+        pow_x((1 << 64) - 1, &XOROSHIRO64, &mut r);
+        assert_eq!(r, [1]);
+    }
+
+    #[test]
+    fn pow_x_inv_small() {
+        let mut r = [0; 2];
+        let mut q = [0; 2];
+        let mut tmp = [0; 2];
+        for &n in &[0, 1, 2, 63, 64, 65, 1000, 1 << 100] {
+            pow_x(n, &XOROSHIRO128, &mut r);
+            pow_x_inv(n, &XOROSHIRO128, &mut q);
+            mul_mod(&r, &q, &XOROSHIRO128, &mut tmp);
+            assert_eq!(tmp, [1, 0]);
+        }
+    }
+
+    #[test]
+    fn pow_x_pow2_small() {
+        let mut r = [0; 2];
+        let mut q = [0; 2];
+        for e in 0..8 {
+            pow_x_pow2(e, &XOROSHIRO128, &mut r);
+            pow_x(1 << e, &XOROSHIRO128, &mut q);
+            assert_eq!(r, q);
+        }
+    }
+
+    #[test]
+    fn pow_x_pow2_period() {
+        let mut r = [0, 0, 0, 0];
+        let mut tmp = [0; 4];
+        r[0] = 2;
+        for _ in 0..256 {
+            mul_mod(&r, &r, &XOSHIRO256, &mut tmp);
+            r = tmp;
+        }
+        assert_eq!(r, [2, 0, 0, 0]);
+        pow_x_pow2(256 + 3, &XOSHIRO256, &mut r);
+        pow_x(1 << 3, &XOSHIRO256, &mut tmp);
+        assert_eq!(r, tmp);
+    }
+
+    #[test]
+    fn charpoly_lfsr() {
+        // An LFSR with the characteristic polynomial x^64 + x^4 + x^3 + x + 1.
+        let mut state = 1u64;
+        let mut p = [0; 1];
+        let l = charpoly(|| {
+            let b = state & 1;
+            let new = (state ^ state >> 1 ^ state >> 3 ^ state >> 4) & 1;
+            state = state >> 1 | new << 63;
+            b != 0
+        }, &mut p);
+        assert_eq!(l, 64);
+        assert_eq!(p, [0b11011]);
+    }
+
+    #[test]
+    fn charpoly_low_complexity() {
+        let mut p = [1; 1];
+        assert_eq!(charpoly(|| false, &mut p), 0);
+        assert_eq!(p, [0]);
+
+        let mut i = 0;
+        assert_eq!(charpoly(|| { i += 1; i % 2 == 0 }, &mut p), 2);
+        assert_eq!(p, [0]);
+
+        // A long run of zeros is followed by a one, so the discrepancy is
+        // only found far beyond the order.
+        let mut i = 0;
+        assert_eq!(charpoly(|| { i += 1; i == 128 }, &mut p), 128);
+        assert_eq!(p, [0]);
+    }
+}
@@ -0,0 +1,597 @@
+//! Generators stepping several independent streams together.
+//!
+//! The state of the streams is stored in structure-of-arrays layout, so each
+//! step is a loop over the lanes doing the same operations, which the compiler
+//! vectorizes for the target CPU. On x86 and x86_64, a version compiled for
+//! AVX2 is selected at runtime if the CPU supports it, with or without the
+//! `std` feature. Without AVX2 (for example with only the SSE2 baseline of
+//! x86_64), the speedup depends on the generator and the number of lanes, and
+//! some of the generators are slower than a single one.
+
+#[cfg(all(not(feature = "std"),
+          any(target_arch = "x86", target_arch = "x86_64")))]
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use rand_core::impls::{fill_bytes_via_next, next_u64_via_u32};
+use rand_core::{Error, RngCore, SeedableRng};
+
+use {Xoshiro128Plus, Xoshiro128StarStar, Xoroshiro64StarStar};
+use {Xoshiro256Plus, Xoshiro256StarStar};
+
+/// Whether the CPU supports AVX2: 0 if it was not detected yet, 1 if it does
+/// not and 2 if it does.
+#[cfg(all(not(feature = "std"),
+          any(target_arch = "x86", target_arch = "x86_64")))]
+static AVX2: AtomicU8 = AtomicU8::new(0);
+
+/// Return whether the CPU supports AVX2.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+fn has_avx2() -> bool {
+    if cfg!(target_feature = "avx2") {
+        return true;
+    }
+    #[cfg(feature = "std")]
+    {
+        is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        match AVX2.load(Ordering::Relaxed) {
+            0 => {
+                let detected = detect_avx2();
+                AVX2.store(if detected { 2 } else { 1 }, Ordering::Relaxed);
+                detected
+            }
+            x => x == 2,
+        }
+    }
+}
+
+/// Detect AVX2 with `cpuid`, for when `is_x86_feature_detected` is not
+/// available.
+///
+/// AVX2 is only usable if the operating system saves the YMM registers on
+/// context switches, which it signals in the XCR0 register. This is also
+/// compiled for the tests, which compare it with `is_x86_feature_detected`.
+#[cfg(all(any(not(feature = "std"), test),
+          any(target_arch = "x86", target_arch = "x86_64")))]
+// The `cpuid` intrinsics are safe in newer versions of Rust.
+#[allow(unused_unsafe)]
+fn detect_avx2() -> bool {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::{__cpuid, __cpuid_count, _xgetbv, has_cpuid};
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::{__cpuid, __cpuid_count, _xgetbv};
+
+    #[target_feature(enable = "xsave")]
+    unsafe fn xcr0() -> u64 {
+        _xgetbv(0)
+    }
+
+    #[cfg(target_arch = "x86")]
+    {
+        if !has_cpuid() {
+            return false;
+        }
+    }
+    // This is safe, because the CPU supports `cpuid`, and `xgetbv` is only
+    // executed if the operating system enabled it.
+    unsafe {
+        if __cpuid(0).eax < 7 {
+            return false;
+        }
+        let ecx = __cpuid(1).ecx;
+        let (osxsave, avx) = (ecx & (1 << 27) != 0, ecx & (1 << 28) != 0);
+        if !osxsave || !avx || xcr0() & 6 != 6 {
+            return false;
+        }
+        __cpuid_count(7, 0).ebx & (1 << 5) != 0
+    }
+}
+
+/// Implement one step of the xoshiro iteration on lane `$i`.
+macro_rules! xoshiro_lane {
+    ($s:ident, $i:ident, $shift:expr, $rot:expr) => {
+        let t = $s[1][$i] << $shift;
+
+        $s[2][$i] ^= $s[0][$i];
+        $s[3][$i] ^= $s[1][$i];
+        $s[1][$i] ^= $s[2][$i];
+        $s[0][$i] ^= $s[3][$i];
+
+        $s[2][$i] ^= t;
+
+        $s[3][$i] = $s[3][$i].rotate_left($rot);
+    }
+}
+
+/// Implement one step of the xoroshiro iteration for `u32` output on lane
+/// `$i`.
+macro_rules! xoroshiro_u32_lane {
+    ($s:ident, $i:ident) => {
+        $s[1][$i] ^= $s[0][$i];
+        $s[0][$i] = $s[0][$i].rotate_left(26) ^ $s[1][$i] ^ ($s[1][$i] << 9);
+        $s[1][$i] = $s[1][$i].rotate_left(13);
+    }
+}
+
+/// Return the documentation of a multi-lane generator.
+macro_rules! lanes_doc {
+    ($Lanes:ident, $Rng:ident, $n:expr, $fill:ident, $next:ident,
+     $seed_u64:ident) => {
+        concat!(
+"A ", stringify!($Rng), " random number generator with ", stringify!($n),
+" lanes, stepped together.
+
+Lane `i` is a `", stringify!($Rng), "` that was advanced with `jump()` `i`
+times, so the lanes are independent streams that do not overlap. The output
+of `RngCore` and `", stringify!($fill), "` is the interleaving of the lanes:
+the first output of lane 0, then the first output of lane 1, and so on, before
+the second output of lane 0.
+
+Stepping the lanes together avoids the latency of the dependency chain of a
+single generator. On CPUs with AVX2, filling large buffers with
+`", stringify!($fill), "` is more than twice as fast as with a single
+generator. Without AVX2, it can be slower than with a single generator, see
+the crate documentation. `next_lanes` returns one output of each lane.
+
+```
+# extern crate xoshiro;
+# fn main() {
+use xoshiro::{", stringify!($Rng), ", ", stringify!($Lanes), "};
+
+let mut rng = ", stringify!($Lanes), "::from_seed_u64(0);
+let mut buf = [0; 1000];
+rng.", stringify!($fill), "(&mut buf);
+
+let mut lane1 = ", stringify!($Rng), "::", stringify!($seed_u64), "(0);
+lane1.jump();
+assert_eq!(buf[1], lane1.", stringify!($next), "().0);
+# }
+```")
+    }
+}
+
+/// Implement a multi-lane generator with `$n` lanes of the generator `$Rng`,
+/// which has `$words` state words of type `$word`.
+///
+/// `$fill` is the name of the method filling a slice with output, `$next` the
+/// name of the `const fn` of `$Rng` returning the next output and `$seed_u64`
+/// the name of the function of `$Rng` used by `from_seed_u64`. `$step`
+/// calculates the output of lane `$i` of the state `$s` and advances
+/// it.
+macro_rules! impl_lanes {
+    ($Lanes:ident, $Rng:ident, $n:expr, $word:ident, $words:expr,
+     $fill:ident, $next:ident, $seed_u64:ident,
+     |$s:ident, $i:ident| $step:block) => {
+        #[doc = lanes_doc!($Lanes, $Rng, $n, $fill, $next, $seed_u64)]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $Lanes {
+            /// The state words, indexed by word and lane.
+            s: [[$word; $n]; $words],
+            /// Buffered output of the last step.
+            buf: [$word; $n],
+            /// The index of the next output in `buf`.
+            index: usize,
+        }
+
+        impl $Lanes {
+            /// The number of lanes.
+            pub const LANES: usize = $n;
+
+            #[doc = concat!("Create a new `", stringify!($Lanes), "` from a \
+                             `", stringify!($Rng), "`.")]
+            ///
+            /// Lane `i` starts at the state of `rng` after `i` calls to
+            /// `jump()`.
+            pub fn new(rng: &$Rng) -> $Lanes {
+                let mut rng = rng.clone();
+                let mut s = [[0; $n]; $words];
+                for i in 0..$n {
+                    let state = rng.state();
+                    for (w, &x) in s.iter_mut().zip(&state) {
+                        w[i] = x;
+                    }
+                    rng.jump();
+                }
+                $Lanes { s, buf: [0; $n], index: $n }
+            }
+
+            #[doc = concat!("Seed a `", stringify!($Lanes), "` from a `u64`, \
+                             like `", stringify!($Rng), "::",
+                             stringify!($seed_u64), "`.")]
+            pub fn from_seed_u64(seed: u64) -> $Lanes {
+                $Lanes::new(&$Rng::$seed_u64(seed))
+            }
+
+            #[doc = concat!("Return the `", stringify!($Rng), "` of lane `i`.")]
+            ///
+            /// It continues after the last step of the lanes, so it does not
+            /// repeat the output buffered for `RngCore`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `i` is not less than the number of lanes.
+            pub fn lane(&self, i: usize) -> $Rng {
+                let mut state = [0; $words];
+                for (x, w) in state.iter_mut().zip(&self.s) {
+                    *x = w[i];
+                }
+                $Rng::from_state(state).unwrap()
+            }
+
+            /// Advance all lanes, returning one output of each of them.
+            ///
+            /// This bypasses the buffered output of `RngCore`.
+            #[inline]
+            pub fn next_lanes(&mut self) -> [$word; $n] {
+                let mut out = [0; $n];
+                $Lanes::step(&mut self.s, &mut out);
+                out
+            }
+
+            #[inline(always)]
+            #[allow(clippy::needless_range_loop)]
+            fn step(s: &mut [[$word; $n]; $words], out: &mut [$word; $n]) {
+                let $s = s;
+                for $i in 0..$n {
+                    out[$i] = $step;
+                }
+            }
+
+            /// Fill `dest`, whose length is a multiple of the number of lanes,
+            /// with whole steps.
+            #[inline(always)]
+            fn fill_steps(s: &mut [[$word; $n]; $words], dest: &mut [$word]) {
+                let mut out = [0; $n];
+                for chunk in dest.chunks_exact_mut($n) {
+                    $Lanes::step(s, &mut out);
+                    chunk.copy_from_slice(&out);
+                }
+            }
+
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            #[target_feature(enable = "avx2")]
+            unsafe fn fill_steps_avx2(s: &mut [[$word; $n]; $words],
+                                      dest: &mut [$word])
+            {
+                $Lanes::fill_steps(s, dest);
+            }
+
+            /// Fill `dest` with whole steps, using the fastest implementation
+            /// supported by the CPU.
+            fn fill_steps_dispatch(s: &mut [[$word; $n]; $words],
+                                   dest: &mut [$word])
+            {
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                {
+                    if has_avx2() {
+                        // This is safe, because the CPU supports AVX2.
+                        unsafe { $Lanes::fill_steps_avx2(s, dest) };
+                        return;
+                    }
+                }
+                $Lanes::fill_steps(s, dest);
+            }
+
+            /// Fill `dest` with the interleaved output of the lanes, continuing
+            /// the output of `RngCore`.
+            pub fn $fill(&mut self, dest: &mut [$word]) {
+                // Use up the buffered output first.
+                let k = ::core::cmp::min($n - self.index, dest.len());
+                let buf = &self.buf[self.index..];
+                dest[..k].copy_from_slice(&buf[..k]);
+                self.index += k;
+                let dest = &mut dest[k..];
+
+                let whole = dest.len() / $n * $n;
+                let (whole, rest) = dest.split_at_mut(whole);
+                $Lanes::fill_steps_dispatch(&mut self.s, whole);
+                if !rest.is_empty() {
+                    $Lanes::step(&mut self.s, &mut self.buf);
+                    rest.copy_from_slice(&self.buf[..rest.len()]);
+                    self.index = rest.len();
+                }
+            }
+
+            /// Return the next buffered output, refilling the buffer if it is
+            /// empty.
+            #[inline]
+            fn next(&mut self) -> $word {
+                if self.index == $n {
+                    $Lanes::step(&mut self.s, &mut self.buf);
+                    self.index = 0;
+                }
+                let x = self.buf[self.index];
+                self.index += 1;
+                x
+            }
+        }
+
+        impl SeedableRng for $Lanes {
+            type Seed = <$Rng as SeedableRng>::Seed;
+
+            #[doc = concat!("Create a new `", stringify!($Lanes), "` from a \
+                             seed, like `", stringify!($Rng), "::from_seed`.")]
+            #[inline]
+            fn from_seed(seed: Self::Seed) -> $Lanes {
+                $Lanes::new(&$Rng::from_seed(seed))
+            }
+        }
+
+        impl_lanes!(@rng_core $Lanes, $word);
+
+        impl_block_rng_core!($Lanes, $word, $fill);
+    };
+    (@rng_core $Lanes:ident, u32) => {
+        impl RngCore for $Lanes {
+            #[inline]
+            fn next_u32(&mut self) -> u32 {
+                self.next()
+            }
+
+            #[inline]
+            fn next_u64(&mut self) -> u64 {
+                next_u64_via_u32(self)
+            }
+
+            #[inline]
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                fill_bytes_via_next(self, dest);
+            }
+
+            #[inline]
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+    };
+    (@rng_core $Lanes:ident, u64) => {
+        impl RngCore for $Lanes {
+            #[inline]
+            fn next_u32(&mut self) -> u32 {
+                // The lowest bits have some linear dependencies, so we use the
+                // upper bits instead.
+                (self.next() >> 32) as u32
+            }
+
+            #[inline]
+            fn next_u64(&mut self) -> u64 {
+                self.next()
+            }
+
+            #[inline]
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                fill_bytes_via_next(self, dest);
+            }
+
+            #[inline]
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+    };
+}
+
+
+impl_lanes!(Xoshiro256StarStarX4, Xoshiro256StarStar, 4, u64, 4,
+            fill_u64, const_next_u64, from_seed_u64, |s, i| {
+    let result_starstar = starstar_u64!(s[1][i]);
+    xoshiro_lane!(s, i, 17, 45);
+    result_starstar
+});
+
+impl_lanes!(Xoshiro256StarStarX8, Xoshiro256StarStar, 8, u64, 4,
+            fill_u64, const_next_u64, from_seed_u64, |s, i| {
+    let result_starstar = starstar_u64!(s[1][i]);
+    xoshiro_lane!(s, i, 17, 45);
+    result_starstar
+});
+
+impl_lanes!(Xoshiro256PlusX4, Xoshiro256Plus, 4, u64, 4,
+            fill_u64, const_next_u64, from_seed_u64, |s, i| {
+    let result_plus = s[0][i].wrapping_add(s[3][i]);
+    xoshiro_lane!(s, i, 17, 45);
+    result_plus
+});
+
+impl_lanes!(Xoshiro256PlusX8, Xoshiro256Plus, 8, u64, 4,
+            fill_u64, const_next_u64, from_seed_u64, |s, i| {
+    let result_plus = s[0][i].wrapping_add(s[3][i]);
+    xoshiro_lane!(s, i, 17, 45);
+    result_plus
+});
+
+impl_lanes!(Xoshiro128StarStarX4, Xoshiro128StarStar, 4, u32, 4,
+            fill_u32, const_next_u32, from_seed_u64, |s, i| {
+    let result_starstar = starstar_u64!(s[0][i]);
+    xoshiro_lane!(s, i, 9, 11);
+    result_starstar
+});
+
+impl_lanes!(Xoshiro128StarStarX8, Xoshiro128StarStar, 8, u32, 4,
+            fill_u32, const_next_u32, from_seed_u64, |s, i| {
+    let result_starstar = starstar_u64!(s[0][i]);
+    xoshiro_lane!(s, i, 9, 11);
+    result_starstar
+});
+
+impl_lanes!(Xoshiro128StarStarX16, Xoshiro128StarStar, 16, u32, 4,
+            fill_u32, const_next_u32, from_seed_u64, |s, i| {
+    let result_starstar = starstar_u64!(s[0][i]);
+    xoshiro_lane!(s, i, 9, 11);
+    result_starstar
+});
+
+impl_lanes!(Xoshiro128PlusX4, Xoshiro128Plus, 4, u32, 4,
+            fill_u32, const_next_u32, from_seed_u64, |s, i| {
+    let result_plus = s[0][i].wrapping_add(s[3][i]);
+    xoshiro_lane!(s, i, 9, 11);
+    result_plus
+});
+
+impl_lanes!(Xoshiro128PlusX8, Xoshiro128Plus, 8, u32, 4,
+            fill_u32, const_next_u32, from_seed_u64, |s, i| {
+    let result_plus = s[0][i].wrapping_add(s[3][i]);
+    xoshiro_lane!(s, i, 9, 11);
+    result_plus
+});
+
+impl_lanes!(Xoshiro128PlusX16, Xoshiro128Plus, 16, u32, 4,
+            fill_u32, const_next_u32, from_seed_u64, |s, i| {
+    let result_plus = s[0][i].wrapping_add(s[3][i]);
+    xoshiro_lane!(s, i, 9, 11);
+    result_plus
+});
+
+impl_lanes!(Xoroshiro64StarStarX4, Xoroshiro64StarStar, 4, u32, 2,
+            fill_u32, const_next_u32, from_seed_u64_v2, |s, i| {
+    let r = starstar_u32!(s[0][i]);
+    xoroshiro_u32_lane!(s, i);
+    r
+});
+
+impl_lanes!(Xoroshiro64StarStarX8, Xoroshiro64StarStar, 8, u32, 2,
+            fill_u32, const_next_u32, from_seed_u64_v2, |s, i| {
+    let r = starstar_u32!(s[0][i]);
+    xoroshiro_u32_lane!(s, i);
+    r
+});
+
+impl_lanes!(Xoroshiro64StarStarX16, Xoroshiro64StarStar, 16, u32, 2,
+            fill_u32, const_next_u32, from_seed_u64_v2, |s, i| {
+    let r = starstar_u32!(s[0][i]);
+    xoroshiro_u32_lane!(s, i);
+    r
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::block::BlockRngCore;
+
+    macro_rules! test_lanes {
+        ($name:ident, $Lanes:ident, $Rng:ident, $fill:ident,
+         $next_core:ident, $seed_u64:ident) => {
+            #[test]
+            fn $name() {
+                let n = $Lanes::LANES;
+                let mut rng = $Lanes::from_seed_u64(7);
+                let mut buf = [0; 103];
+                for x in &mut buf[..3] {
+                    *x = rng.$next_core();
+                }
+                rng.$fill(&mut buf[3..50]);
+                rng.$fill(&mut buf[50..51]);
+                rng.$fill(&mut buf[51..]);
+
+                // The output is the interleaving of streams created by
+                // jumping.
+                let mut base = $Rng::$seed_u64(7);
+                for i in 0..n {
+                    let mut lane = base.clone();
+                    for &x in buf.iter().skip(i).step_by(n) {
+                        assert_eq!(x, lane.$next_core());
+                    }
+                    base.jump();
+                }
+
+                // The lanes continue after the last step.
+                let next = rng.clone().next_lanes();
+                for (i, &x) in next.iter().enumerate() {
+                    assert_eq!(rng.lane(i).$next_core(), x);
+                }
+
+                // The AVX2 implementation agrees with the scalar one.
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                {
+                    if has_avx2() {
+                        let mut s1 = rng.s;
+                        let mut s2 = rng.s;
+                        let mut buf1 = [0; 96];
+                        let mut buf2 = [0; 96];
+                        $Lanes::fill_steps(&mut s1, &mut buf1);
+                        // This is safe, because the CPU supports AVX2.
+                        unsafe { $Lanes::fill_steps_avx2(&mut s2, &mut buf2) };
+                        assert_eq!(s1, s2);
+                        assert_eq!(buf1[..], buf2[..]);
+                    }
+                }
+
+                let seed = Default::default();
+                assert_eq!($Lanes::from_seed(seed),
+                           $Lanes::new(&$Rng::from_seed(seed)));
+
+                let mut rng1 = $Lanes::from_seed_u64(7);
+                let mut rng2 = rng1.clone();
+                let mut block = Default::default();
+                rng1.generate(&mut block);
+                for &x in block.iter() {
+                    assert_eq!(x, rng2.$next_core());
+                }
+            }
+        }
+    }
+
+    #[cfg(all(feature = "std",
+              any(target_arch = "x86", target_arch = "x86_64")))]
+    #[test]
+    fn detect_avx2_std() {
+        assert_eq!(detect_avx2(), is_x86_feature_detected!("avx2"));
+    }
+
+    #[test]
+    fn xoroshiro64starstar_seed_u64() {
+        // The lanes are seeded with `from_seed_u64_v2`, not with the raw
+        // state like `Xoroshiro64StarStar::from_seed_u64`.
+        for &seed in &[0, 1, 7, !0] {
+            let x4 = Xoroshiro64StarStarX4::from_seed_u64(seed);
+            let x8 = Xoroshiro64StarStarX8::from_seed_u64(seed);
+            let x16 = Xoroshiro64StarStarX16::from_seed_u64(seed);
+            if seed != 0 {
+                assert!(x4.lane(0) != Xoroshiro64StarStar::from_seed_u64(seed));
+            }
+            let mut lane = Xoroshiro64StarStar::from_seed_u64_v2(seed);
+            for i in 0..16 {
+                if i < 4 {
+                    assert_eq!(x4.lane(i), lane);
+                }
+                if i < 8 {
+                    assert_eq!(x8.lane(i), lane);
+                }
+                assert_eq!(x16.lane(i), lane);
+                lane.jump();
+            }
+        }
+    }
+
+    test_lanes!(xoshiro256starstar_x4, Xoshiro256StarStarX4,
+                Xoshiro256StarStar, fill_u64, next_u64, from_seed_u64);
+    test_lanes!(xoshiro256starstar_x8, Xoshiro256StarStarX8,
+                Xoshiro256StarStar, fill_u64, next_u64, from_seed_u64);
+    test_lanes!(xoshiro256plus_x4, Xoshiro256PlusX4,
+                Xoshiro256Plus, fill_u64, next_u64, from_seed_u64);
+    test_lanes!(xoshiro256plus_x8, Xoshiro256PlusX8,
+                Xoshiro256Plus, fill_u64, next_u64, from_seed_u64);
+    test_lanes!(xoshiro128starstar_x4, Xoshiro128StarStarX4,
+                Xoshiro128StarStar, fill_u32, next_u32, from_seed_u64);
+    test_lanes!(xoshiro128starstar_x8, Xoshiro128StarStarX8,
+                Xoshiro128StarStar, fill_u32, next_u32, from_seed_u64);
+    test_lanes!(xoshiro128starstar_x16, Xoshiro128StarStarX16,
+                Xoshiro128StarStar, fill_u32, next_u32, from_seed_u64);
+    test_lanes!(xoshiro128plus_x4, Xoshiro128PlusX4,
+                Xoshiro128Plus, fill_u32, next_u32, from_seed_u64);
+    test_lanes!(xoshiro128plus_x8, Xoshiro128PlusX8,
+                Xoshiro128Plus, fill_u32, next_u32, from_seed_u64);
+    test_lanes!(xoshiro128plus_x16, Xoshiro128PlusX16,
+                Xoshiro128Plus, fill_u32, next_u32, from_seed_u64);
+    test_lanes!(xoroshiro64starstar_x4, Xoroshiro64StarStarX4,
+                Xoroshiro64StarStar, fill_u32, next_u32, from_seed_u64_v2);
+    test_lanes!(xoroshiro64starstar_x8, Xoroshiro64StarStarX8,
+                Xoroshiro64StarStar, fill_u32, next_u32, from_seed_u64_v2);
+    test_lanes!(xoroshiro64starstar_x16, Xoroshiro64StarStarX16,
+                Xoroshiro64StarStar, fill_u32, next_u32, from_seed_u64_v2);
+}
@@ -9,6 +9,9 @@
 //! # 64-bit generators
 //! - [`Xoshiro256StarStar`]: Recommended for all purposes. Excellent speed and
 //!   a state space (256 bits) large enough for any parallel application.
+//! - [`Xoshiro256PlusPlus`]: Recommended for all purposes. An alternative to
+//!   `Xoshiro256StarStar` using the ++ scrambler, having the same speed and
+//!   state space.
 //! - [`Xoshiro256Plus`]: Recommended for generating 64-bit floating-point
 //!   numbers. About 15% faster than `Xoshiro256StarStar`, but has a [low linear
 //!   complexity] in the lowest bits (which are discarded when generating
@@ -17,6 +20,9 @@
 //! - [`Xoroshiro128StarStar`]: An alternative to `Xoshiro256StarStar`, having
 //!   the same speed but using half the state. Only suited for low-scale parallel
 //!   applications.
+//! - [`Xoroshiro128PlusPlus`]: An alternative to `Xoroshiro128StarStar` using
+//!   the ++ scrambler, having the same speed and state size. Only suited for
+//!   low-scale parallel applications.
 //! - [`Xoroshiro128Plus`]: An alternative to `Xoshiro256Plus`, having the same
 //!   speed but using half the state. Only suited for low-scale parallel
 //!   applications. Has a [low linear complexity] in the lowest bits (which are
@@ -24,15 +30,27 @@
 //!   unlikely to have any impact in practise.
 //! - [`Xoshiro512StarStar`]: An alternative to `Xoshiro256StarStar` with more
 //!   state and the same speed.
+//! - [`Xoshiro512PlusPlus`]: An alternative to `Xoshiro256PlusPlus` with more
+//!   state and the same speed.
 //! - [`Xoshiro512Plus`]: An alternative to `Xoshiro512Plus` with more
 //!   state and the same speed. Has a [low linear complexity] in the lowest bits
 //!   (which are discarded when generating floats), making it fail linearity
 //!   tests. This is unlikely to have any impact in practise.
+//! - [`Xoroshiro1024StarStar`]: An alternative to `Xoshiro512StarStar` with
+//!   twice the state, for massively parallel applications.
+//! - [`Xoroshiro1024PlusPlus`]: An alternative to `Xoshiro512PlusPlus` with
+//!   twice the state, for massively parallel applications.
+//! - [`Xoroshiro1024Star`]: An alternative to `Xoshiro512Plus` with twice the
+//!   state, for massively parallel applications. Has a [low linear complexity]
+//!   in the lowest bits (which are discarded when generating floats), making it
+//!   fail linearity tests. This is unlikely to have any impact in practise.
 //! - [`SplitMix64`]: Recommended for initializing generators of the xoshiro
 //!   familiy from a 64-bit seed. Used for implementing `seed_from_u64`.
 //!
 //! # 32-bit generators
 //! - [`Xoshiro128StarStar`]: Recommended for all purposes. Excellent speed.
+//! - [`Xoshiro128PlusPlus`]: Recommended for all purposes. An alternative to
+//!   `Xoshiro128StarStar` using the ++ scrambler, having the same speed.
 //! - [`Xoshiro128Plus`]: Recommended for generating 32-bit floating-point
 //!   numbers. Faster than `Xoshiro128StarStar`, but has a [low linear
 //!   complexity] in the lowest bits (which are discarded when generating
@@ -45,48 +63,188 @@
 //!   lowest bits (which are discarded when generating floats), making it fail
 //!   linearity tests. This is unlikely to have any impact in practise.
 //!
+//! # Multi-lane generators
+//! [`Xoshiro256StarStarX4`], [`Xoshiro256StarStarX8`], [`Xoshiro256PlusX4`]
+//! and [`Xoshiro256PlusX8`] step 4 or 8 streams of the corresponding generator
+//! together. Their output is the interleaving of the streams, which are
+//! created with `jump()`. Likewise, there are 32-bit generators with 4, 8 or 16
+//! lanes of `Xoshiro128StarStar`, `Xoshiro128Plus` and `Xoroshiro64StarStar`,
+//! for example [`Xoshiro128StarStarX8`], which fill `u32` buffers with
+//! `fill_u32`.
+//!
+//! The lanes are stepped with SIMD instructions as far as the target CPU
+//! supports them. On x86 and x86_64, AVX2 is detected at runtime (also without
+//! the `std` feature), and with it `fill_u64` and `fill_u32` are more than
+//! twice as fast as with a single generator. Without AVX2, only the lanes of
+//! `Xoshiro128StarStar` and `Xoshiro128Plus` are faster than a single
+//! generator. The others are slower, because SSE2, which all x86_64 CPUs
+//! support, processes only two 64-bit words at a time and cannot multiply
+//! 32-bit words, so they are only worth using if AVX2 is expected to be
+//! available.
+//!
+//! # Block generators
+//! All generators implement `rand_core::block::BlockRngCore`, generating
+//! blocks of 32 words with `fill_u64` or `fill_u32`. They can therefore be
+//! wrapped in `BlockRng64` (or `BlockRng` for the 32-bit generators), which
+//! buffers the output. On CPUs with AVX2, this speeds up `fill_bytes` for the
+//! multi-lane generators. The `RngCore` implementations of the other
+//! generators are already faster than the buffered ones.
+//!
+//! # Floating-point numbers
+//! All generators provide `next_f64` and `next_f32`, which return uniformly
+//! distributed numbers in [0, 1) generated from the upper 53 and 24 bits of
+//! the output. They are therefore not affected by the low linear complexity of
+//! the lowest bits of the + generators. Variants for the intervals (0, 1] and
+//! (0, 1) are available, as well as `next_f64_full` and `next_f32_full`, which
+//! can return every representable number in [0, 1).
+//!
+//! # Serialization
+//! With the `serde` feature enabled, all generators and seeds implement
+//! `Serialize` and `Deserialize`. A generator is serialized as a struct with
+//! the same name, containing its internal state:
+//!
+//! - `s`: the state words of the xoshiro generators, in the order of the
+//!   reference implementation.
+//! - `s0`, `s1`: the state words of the two-word xoroshiro generators.
+//! - `s`, `p`: the state words of the xoroshiro1024 generators, and the index
+//!   of the current position in them.
+//! - `x`: the state of `SplitMix64`.
+//!
+//! Deserializing an all-zero state of a xoshiro or xoroshiro generator fails,
+//! because the generator would only produce zeros. Seeds are serialized as
+//! tuples of bytes.
+//!
+//! # Crate features
+//! The crate is `no_std` by default, so all generators can be used on
+//! bare-metal targets. The following features are available:
+//!
+//! - `std`: Enables `streams` and the binary checkpoints described below,
+//!   which need allocation and `std::io`.
+//! - `serde`: Enables serialization, see below.
+//!
+//! # Checkpoints
+//! With the `std` feature enabled, all generators can write their state to a
+//! `std::io::Write` with `write_checkpoint`, and be restored from a
+//! `std::io::Read` with `read_checkpoint`. Unlike the serde support, this
+//! format identifies the generator, so restoring a checkpoint into a different
+//! generator (for example a `Xoshiro256Plus` checkpoint into a
+//! `Xoshiro256StarStar`) fails instead of silently producing a different
+//! sequence. A checkpoint consists of:
+//!
+//! - the 4 bytes `XSHR`,
+//! - the version of the format (currently 1) as one byte,
+//! - the algorithm identifier as one byte (see below),
+//! - the size of a state word in bytes (4 or 8) as one byte,
+//! - the number of state words as one byte,
+//! - the state words in little-endian byte order, in the same order as for
+//!   serialization (the circular buffer of the xoroshiro1024 generators is
+//!   rotated to start at the current position),
+//! - the CRC-32 (as used by zlib) of all the preceding bytes, in little-endian
+//!   byte order.
+//!
+//! The algorithm identifiers are: 1 `SplitMix64`, 2 `Xoroshiro64Star`,
+//! 3 `Xoroshiro64StarStar`, 4 `Xoroshiro128Plus`, 5 `Xoroshiro128StarStar`,
+//! 6 `Xoroshiro128PlusPlus`, 7 `Xoshiro128Plus`, 8 `Xoshiro128StarStar`,
+//! 9 `Xoshiro128PlusPlus`, 10 `Xoshiro256Plus`, 11 `Xoshiro256StarStar`,
+//! 12 `Xoshiro256PlusPlus`, 13 `Xoshiro512Plus`, 14 `Xoshiro512StarStar`,
+//! 15 `Xoshiro512PlusPlus`, 16 `Xoroshiro1024Star`, 17 `Xoroshiro1024StarStar`
+//! and 18 `Xoroshiro1024PlusPlus`.
+//!
+//! Reading a checkpoint fails with `std::io::ErrorKind::InvalidData` if it was
+//! written by a different generator or a different version of the format, if
+//! the checksum does not match, or if it contains the all-zero state of a
+//! xoshiro or xoroshiro generator.
+//!
 //! [xoshiro]: http://xoshiro.di.unimi.it/
 //! [low linear complexity]: http://xoshiro.di.unimi.it/lowcomp.php
 //! [`Xoshiro256StarStar`]: ./struct.Xoshiro256StarStar.html
 //! [`Xoshiro256Plus`]: ./struct.Xoshiro256Plus.html
+//! [`Xoshiro256PlusPlus`]: ./struct.Xoshiro256PlusPlus.html
 //! [`Xoroshiro128StarStar`]: ./struct.Xoroshiro128StarStar.html
+//! [`Xoroshiro128PlusPlus`]: ./struct.Xoroshiro128PlusPlus.html
 //! [`Xoroshiro128Plus`]: ./struct.Xoroshiro128Plus.html
 //! [`Xoshiro512StarStar`]: ./struct.Xoshiro512StarStar.html
+//! [`Xoshiro512PlusPlus`]: ./struct.Xoshiro512PlusPlus.html
 //! [`Xoshiro512Plus`]: ./struct.Xoshiro512Plus.html
+//! [`Xoroshiro1024StarStar`]: ./struct.Xoroshiro1024StarStar.html
+//! [`Xoroshiro1024PlusPlus`]: ./struct.Xoroshiro1024PlusPlus.html
+//! [`Xoroshiro1024Star`]: ./struct.Xoroshiro1024Star.html
 //! [`SplitMix64`]: ./struct.SplitMix64.html
 //! [`Xoshiro128StarStar`]: ./struct.Xoshiro128StarStar.html
+//! [`Xoshiro128PlusPlus`]: ./struct.Xoshiro128PlusPlus.html
 //! [`Xoshiro128Plus`]: ./struct.Xoshiro128Plus.html
 //! [`Xoroshiro64StarStar`]: ./struct.Xoroshiro64StarStar.html
 //! [`Xoroshiro64Star`]: ./struct.Xoroshiro64Star.html
+//! [`Xoshiro256StarStarX4`]: ./struct.Xoshiro256StarStarX4.html
+//! [`Xoshiro256StarStarX8`]: ./struct.Xoshiro256StarStarX8.html
+//! [`Xoshiro256PlusX4`]: ./struct.Xoshiro256PlusX4.html
+//! [`Xoshiro256PlusX8`]: ./struct.Xoshiro256PlusX8.html
+//! [`Xoshiro128StarStarX8`]: ./struct.Xoshiro128StarStarX8.html
 
-#![cfg_attr(feature = "cargo-clippy", allow(unreadable_literal))]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unreadable_literal)]
 
 extern crate byteorder;
+#[cfg(feature = "std")]
+extern crate core;
 extern crate rand_core;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
 #[macro_use]
 mod common;
+#[cfg(feature = "std")]
+mod checkpoint;
+mod float;
+mod gf2;
+mod lanes;
+mod seed_sequence;
 mod splitmix64;
 mod xoshiro128starstar;
 mod xoshiro128plus;
+mod xoshiro128plusplus;
 mod xoshiro256starstar;
 mod xoshiro256plus;
+mod xoshiro256plusplus;
 mod xoshiro512starstar;
 mod xoshiro512plus;
+mod xoshiro512plusplus;
 mod xoroshiro128plus;
 mod xoroshiro128starstar;
+mod xoroshiro128plusplus;
+mod xoroshiro1024star;
+mod xoroshiro1024starstar;
+mod xoroshiro1024plusplus;
 mod xoroshiro64starstar;
 mod xoroshiro64star;
 
+pub use lanes::{Xoshiro256StarStarX4, Xoshiro256StarStarX8};
+pub use lanes::{Xoshiro256PlusX4, Xoshiro256PlusX8};
+pub use lanes::{Xoshiro128StarStarX4, Xoshiro128StarStarX8};
+pub use lanes::Xoshiro128StarStarX16;
+pub use lanes::{Xoshiro128PlusX4, Xoshiro128PlusX8, Xoshiro128PlusX16};
+pub use lanes::{Xoroshiro64StarStarX4, Xoroshiro64StarStarX8};
+pub use lanes::Xoroshiro64StarStarX16;
+pub use seed_sequence::SeedSequence;
 pub use splitmix64::SplitMix64;
 pub use xoshiro128starstar::Xoshiro128StarStar;
 pub use xoshiro128plus::Xoshiro128Plus;
+pub use xoshiro128plusplus::Xoshiro128PlusPlus;
 pub use xoshiro256starstar::Xoshiro256StarStar;
 pub use xoshiro256plus::Xoshiro256Plus;
-pub use common::Seed512;
+pub use xoshiro256plusplus::Xoshiro256PlusPlus;
+pub use common::{Seed64, Seed128, Seed256, Seed512, Seed1024};
+pub use common::{ParseSeedError, ZeroStateError};
 pub use xoshiro512starstar::Xoshiro512StarStar;
 pub use xoshiro512plus::Xoshiro512Plus;
+pub use xoshiro512plusplus::Xoshiro512PlusPlus;
 pub use xoroshiro128plus::Xoroshiro128Plus;
 pub use xoroshiro128starstar::Xoroshiro128StarStar;
+pub use xoroshiro128plusplus::Xoroshiro128PlusPlus;
+pub use xoroshiro1024star::Xoroshiro1024Star;
+pub use xoroshiro1024starstar::Xoroshiro1024StarStar;
+pub use xoroshiro1024plusplus::Xoroshiro1024PlusPlus;
 pub use xoroshiro64starstar::Xoroshiro64StarStar;
 pub use xoroshiro64star::Xoroshiro64Star;
@@ -0,0 +1,214 @@
+use byteorder::{ByteOrder, LittleEndian};
+use rand_core::SeedableRng;
+
+use splitmix64::mix_u64;
+
+/// Added to the words when mixing them.
+const ROUND: u64 = 0x9e3779b97f4a7c15;
+/// Added to the state when generating seeds.
+const GENERATE: u64 = 0x8c2b0f6f5e8d4a31;
+/// Added to the state when spawning children.
+const SPAWN: u64 = 0x3e5a7a9d1c4b6f27;
+
+/// A sequence of seeds derived from an arbitrary number of `u64` words, in the
+/// spirit of NumPy's `SeedSequence`.
+///
+/// The entropy words are mixed into a 256-bit state using the mixing function
+/// of `SplitMix64`. Inputs of up to three words are mapped to distinct states,
+/// so for example 128-bit IDs never collide. From the state, seeds of any
+/// length can be generated, and independent child sequences can be spawned
+/// hierarchically, for example one per thread of a parallel computation.
+///
+/// ```
+/// # extern crate xoshiro;
+/// # fn main() {
+/// use xoshiro::{SeedSequence, Xoshiro256StarStar, Xoshiro512StarStar};
+///
+/// // A 128-bit experiment ID.
+/// let id: u128 = 0x0123456789abcdef_fedcba9876543210;
+/// let mut root = SeedSequence::new(&[id as u64, (id >> 64) as u64]);
+/// let worker1 = root.spawn();
+/// let worker2 = root.spawn();
+/// let rng1: Xoshiro256StarStar = worker1.seed_rng();
+/// let rng2 = Xoshiro512StarStar::from_seed_sequence(&worker2);
+/// # let _ = (rng1, rng2);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedSequence {
+    state: [u64; 4],
+    spawned: u64,
+}
+
+/// Apply a bijective mixing function to the state.
+///
+/// Every step replaces one word by a function of it and its predecessor,
+/// which is invertible. After two rounds, every word depends on all others.
+fn permute(s: &mut [u64; 4]) {
+    for _ in 0..2 {
+        for i in 0..4 {
+            s[i] = mix_u64(s[i] ^ s[(i + 3) & 3].wrapping_add(ROUND));
+        }
+    }
+}
+
+impl SeedSequence {
+    /// Create a new `SeedSequence` from the given entropy words.
+    pub fn new(entropy: &[u64]) -> SeedSequence {
+        // The words are absorbed in blocks of three, the last word of the
+        // state is reserved for the length.
+        let mut state = [0; 4];
+        for block in entropy.chunks(3) {
+            for (s, &w) in state.iter_mut().zip(block) {
+                *s ^= w;
+            }
+            permute(&mut state);
+        }
+        state[3] ^= entropy.len() as u64;
+        permute(&mut state);
+        SeedSequence { state, spawned: 0 }
+    }
+
+    /// Spawn a child sequence.
+    ///
+    /// The children are independent of each other and of the parent. The
+    /// `i`-th child spawned from equal sequences is always the same.
+    pub fn spawn(&mut self) -> SeedSequence {
+        let mut state = self.state;
+        state[2] = state[2].wrapping_add(SPAWN);
+        state[3] ^= self.spawned;
+        permute(&mut state);
+        self.spawned += 1;
+        SeedSequence { state, spawned: 0 }
+    }
+
+    /// Return the number of children spawned so far.
+    pub fn spawned(&self) -> u64 {
+        self.spawned
+    }
+
+    /// Fill `words` with seed words derived from the sequence.
+    ///
+    /// Shorter outputs are prefixes of longer ones.
+    pub fn generate_state(&self, words: &mut [u64]) {
+        self.generate_state_at(0, words);
+    }
+
+    /// Fill `bytes` with seed bytes derived from the sequence.
+    ///
+    /// These are the bytes of `generate_state` in little-endian order.
+    pub fn fill_bytes(&self, bytes: &mut [u8]) {
+        let mut words = [0; 16];
+        let mut offset = 0;
+        for chunk in bytes.chunks_mut(8 * words.len()) {
+            self.generate_state_at(offset, &mut words);
+            let mut buf = [0; 8 * 16];
+            LittleEndian::write_u64_into(&words, &mut buf);
+            chunk.copy_from_slice(&buf[..chunk.len()]);
+            offset += words.len();
+        }
+    }
+
+    /// Fill `words` with the seed words starting at `offset`, which has to be
+    /// a multiple of 4.
+    fn generate_state_at(&self, offset: usize, words: &mut [u64]) {
+        debug_assert!(offset & 3 == 0);
+        let mut key = self.state;
+        key[2] = key[2].wrapping_add(GENERATE);
+        permute(&mut key);
+        for (i, chunk) in words.chunks_mut(4).enumerate() {
+            let mut block = key;
+            block[3] ^= (offset / 4 + i) as u64;
+            permute(&mut block);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+
+    /// Create a random number generator seeded from the sequence.
+    ///
+    /// This works for any generator, not only the ones of this crate.
+    pub fn seed_rng<R: SeedableRng>(&self) -> R {
+        let mut seed = R::Seed::default();
+        self.fill_bytes(seed.as_mut());
+        R::from_seed(seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::RngCore;
+
+    fn first_word(seq: &SeedSequence) -> u64 {
+        let mut w = [0; 1];
+        seq.generate_state(&mut w);
+        w[0]
+    }
+
+    #[test]
+    fn distinct() {
+        let inputs: [&[u64]; 6] = [
+            &[], &[0], &[0, 0], &[1], &[1, 0], &[0, 1],
+        ];
+        for (i, a) in inputs.iter().enumerate() {
+            for b in &inputs[..i] {
+                assert!(SeedSequence::new(a) != SeedSequence::new(b));
+                assert!(first_word(&SeedSequence::new(a))
+                        != first_word(&SeedSequence::new(b)));
+            }
+        }
+    }
+
+    #[test]
+    fn values() {
+        // The seeds must not change, so that seeded streams stay
+        // reproducible.
+        let mut words = [0; 2];
+        SeedSequence::new(&[1, 2]).generate_state(&mut words);
+        assert_eq!(words, [0x3d766836d86abcb7, 0xc9464637036ad4b4]);
+    }
+
+    #[test]
+    fn generate_state() {
+        let seq = SeedSequence::new(&[1, 2, 3, 4, 5]);
+        let mut short = [0; 3];
+        let mut long = [0; 40];
+        seq.generate_state(&mut short);
+        seq.generate_state(&mut long);
+        assert_eq!(short, long[..3]);
+
+        let mut bytes = [0; 8 * 40];
+        seq.fill_bytes(&mut bytes[..8 * 40 - 3]);
+        let mut expected = [0; 8 * 40];
+        LittleEndian::write_u64_into(&long, &mut expected);
+        assert_eq!(bytes[..8 * 40 - 3], expected[..8 * 40 - 3]);
+    }
+
+    #[test]
+    fn spawn() {
+        let mut root1 = SeedSequence::new(&[42]);
+        let mut root2 = root1.clone();
+        let child1 = root1.spawn();
+        let child2 = root1.spawn();
+        assert_eq!(root1.spawned(), 2);
+        assert!(child1 != child2);
+        assert!(first_word(&child1) != first_word(&root1));
+        assert!(first_word(&child1) != first_word(&child2));
+        assert_eq!(root2.spawn(), child1);
+        assert_eq!(root2.spawn(), child2);
+
+        // Grandchildren are different from children.
+        let mut child1 = child1;
+        assert!(child1.spawn() != child2);
+    }
+
+    #[test]
+    fn seed_rng() {
+        let seq = SeedSequence::new(&[7]);
+        let mut rng1: ::Xoshiro256StarStar = seq.seed_rng();
+        let mut words = [0; 4];
+        seq.generate_state(&mut words);
+        let mut rng2 = ::Xoshiro256StarStar::from_state(words).unwrap();
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+    }
+}
@@ -13,43 +13,199 @@ use rand_core::{RngCore, SeedableRng, Error};
 /// Sebastiano Vigna. For `next_u32`, a more efficient mixing function taken
 /// from [`dsiutils`](http://dsiutils.di.unimi.it/) is used.
 #[allow(missing_copy_implementations)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct SplitMix64 {
     x: u64,
 }
 
 impl SplitMix64 {
+    /// Create a new `SplitMix64` from its raw state.
+    ///
+    /// This is equivalent to `from_seed_u64`, but is a `const fn`, so it can be
+    /// used to create generators at compile time.
+    pub const fn new(x: u64) -> SplitMix64 {
+        SplitMix64 { x }
+    }
+
+    /// Return the raw state.
+    ///
+    /// This is the inverse of `new`.
+    pub const fn state(&self) -> u64 {
+        self.x
+    }
+
     /// Seed a `SplitMix64` from a `u64`.
     pub fn from_seed_u64(seed: u64) -> SplitMix64 {
         let mut x = [0; 8];
         LittleEndian::write_u64(&mut x, seed);
         SplitMix64::from_seed(x)
     }
+
+    impl_seeding!(SplitMix64, 64);
+
+    /// Return the next random `u32` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u32`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::SplitMix64;
+    ///
+    /// const TABLE: [u32; 64] = {
+    ///     let mut rng = SplitMix64::new(1);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u32();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u32(mut self) -> (u32, Self) {
+        self.x = self.x.wrapping_add(PHI);
+        (mix_u32(self.x), self)
+    }
+
+    /// Return the next random `u64` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u64`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::SplitMix64;
+    ///
+    /// const TABLE: [u64; 64] = {
+    ///     let mut rng = SplitMix64::new(1);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u64();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u64(mut self) -> (u64, Self) {
+        self.x = self.x.wrapping_add(PHI);
+        (mix_u64(self.x), self)
+    }
+
+    impl_floats!(u64);
+
+    impl_fill!();
+
+    /// Advance the state, equivalently to `n` calls to `next_u64()`.
+    ///
+    /// This takes constant time, because the state is a simple counter.
+    pub fn advance(&mut self, n: u128) {
+        // The period is 2^64, so only the lower bits of `n` matter.
+        self.x = self.x.wrapping_add(PHI.wrapping_mul(n as u64));
+    }
+
+    /// Step backwards, undoing one call to `next_u32()`.
+    ///
+    /// Returns the number returned by the undone call.
+    pub fn prev_u32(&mut self) -> u32 {
+        let r = mix_u32(self.x);
+        self.x = self.x.wrapping_sub(PHI);
+        r
+    }
+
+    /// Step backwards, undoing one call to `next_u64()`.
+    ///
+    /// Returns the number returned by the undone call.
+    pub fn prev_u64(&mut self) -> u64 {
+        let r = mix_u64(self.x);
+        self.x = self.x.wrapping_sub(PHI);
+        r
+    }
+
+    /// Retreat the state, equivalently to undoing `n` calls to `next_u64()`.
+    ///
+    /// This is the inverse of `advance`.
+    pub fn retreat(&mut self, n: u128) {
+        self.x = self.x.wrapping_sub(PHI.wrapping_mul(n as u64));
+    }
+
+    impl_checkpoint!(SplitMix64, next_u64, ::checkpoint::SPLITMIX64, x, u64);
 }
 
 const PHI: u64 = 0x9e3779b97f4a7c15;
 
+/// Mix the state into a `u32`.
+#[inline]
+const fn mix_u32(mut z: u64) -> u32 {
+    // David Stafford's
+    // (http://zimbry.blogspot.com/2011/09/better-bit-mixing-improving-on.html)
+    // "Mix4" variant of the 64-bit finalizer in Austin Appleby's
+    // MurmurHash3 algorithm.
+    z = (z ^ (z >> 33)).wrapping_mul(0x62A9D9ED799705F5);
+    z = (z ^ (z >> 28)).wrapping_mul(0xCB24D0A5C88C35B3);
+    (z >> 32) as u32
+}
+
+/// Mix the state into a `u64`.
+#[inline]
+pub const fn mix_u64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Mix `bytes` of arbitrary length into `seed`, whose length has to be a
+/// nonzero multiple of 8 and at most 128.
+///
+/// The bytes are read as little-endian words and chained through `mix_u64`.
+/// Each chained value is added to one word of the seed in turn, so that long
+/// inputs can determine all of it. Finally, every word is mixed with the end
+/// of the chain, so that it depends on all the bytes.
+pub fn seed_from_bytes(bytes: &[u8], seed: &mut [u8]) {
+    debug_assert!(!seed.is_empty() && seed.len() & 7 == 0);
+    let mut acc = [0; 16];
+    let acc = &mut acc[..seed.len() / 8];
+    // Start from the length, so that trailing zeros are not ignored.
+    let mut h = mix_u64((bytes.len() as u64).wrapping_add(PHI));
+    for (i, chunk) in bytes.chunks(8).enumerate() {
+        let mut word = [0; 8];
+        word[..chunk.len()].copy_from_slice(chunk);
+        h = mix_u64((h ^ LittleEndian::read_u64(&word)).wrapping_add(PHI));
+        let n = acc.len();
+        acc[i % n] ^= h;
+    }
+    for a in acc.iter_mut() {
+        h = mix_u64(h.wrapping_add(PHI));
+        *a ^= h;
+    }
+    LittleEndian::write_u64_into(acc, seed);
+}
+
 impl RngCore for SplitMix64 {
     #[inline]
     fn next_u32(&mut self) -> u32 {
         self.x = self.x.wrapping_add(PHI);
-        let mut z = self.x;
-        // David Stafford's
-        // (http://zimbry.blogspot.com/2011/09/better-bit-mixing-improving-on.html)
-        // "Mix4" variant of the 64-bit finalizer in Austin Appleby's
-        // MurmurHash3 algorithm.
-        z = (z ^ (z >> 33)).wrapping_mul(0x62A9D9ED799705F5);
-        z = (z ^ (z >> 28)).wrapping_mul(0xCB24D0A5C88C35B3);
-        (z >> 32) as u32
+        mix_u32(self.x)
     }
 
     #[inline]
     fn next_u64(&mut self) -> u64 {
         self.x = self.x.wrapping_add(PHI);
-        let mut z = self.x;
-        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
-        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
-        z ^ (z >> 31)
+        mix_u64(self.x)
     }
 
     #[inline]
@@ -64,6 +220,8 @@ impl RngCore for SplitMix64 {
     }
 }
 
+impl_block_rng_core!(SplitMix64, u64);
+
 impl SeedableRng for SplitMix64 {
     type Seed = [u8; 8];
 
@@ -81,12 +239,15 @@ impl SeedableRng for SplitMix64 {
 mod tests {
     use super::*;
 
+    test_rng!(SplitMix64, u64, new(1477776061723855037),
+              serde({ x: 0 }, r#"{"x":0}"#), other(Xoroshiro128Plus));
+
     #[test]
     fn reference() {
         let mut rng = SplitMix64::from_seed_u64(1477776061723855037);
         // These values were produced with the reference implementation:
         // http://xoshiro.di.unimi.it/splitmix64.c
-        let expected = vec![
+        let expected = [
             1985237415132408290, 2979275885539914483, 13511426838097143398,
             8488337342461049707, 15141737807933549159, 17093170987380407015,
             16389528042912955399, 13177319091862933652, 10841969400225389492,
@@ -110,12 +271,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn state() {
+        let mut rng1 = SplitMix64::from_seed_u64(0);
+        rng1.next_u64();
+        let mut rng2 = SplitMix64::new(rng1.state());
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+    }
+
+    #[test]
+    fn seed_from_bytes_values() {
+        // The seeds must not change, so that named seeds stay reproducible.
+        let inputs: [&[u8]; 3] = [b"", b"\0", b"experiment 1"];
+        let expected = [
+            [0xa706dd2f4d197e6f, 0x238275bc38fcbe91],
+            [0xefcba9fc19b4e7dd, 0xe28195ddd9ee4956],
+            [0xb61cce73b5e7e404, 0xfee2f95129521414],
+        ];
+        for (input, e) in inputs.iter().zip(expected.iter()) {
+            let mut seed = [0; 16];
+            seed_from_bytes(input, &mut seed);
+            let mut words = [0; 2];
+            read_u64_into(&seed, &mut words);
+            assert_eq!(&words, e);
+        }
+
+        // Every byte of a long input affects every word of a large seed.
+        let mut input = [0; 300];
+        let mut seed = [0; 128];
+        seed_from_bytes(&input, &mut seed);
+        for i in 0..input.len() {
+            input[i] ^= 1;
+            let mut other = [0; 128];
+            seed_from_bytes(&input, &mut other);
+            input[i] ^= 1;
+            for (a, b) in seed.chunks(8).zip(other.chunks(8)) {
+                assert!(a != b);
+            }
+        }
+    }
+
     #[test]
     fn next_u32() {
         let mut rng = SplitMix64::from_seed_u64(10);
         // These values were produced with the reference implementation:
         // http://dsiutils.di.unimi.it/dsiutils-2.5.1-src.tar.gz
-        let expected = vec![
+        let expected = [
             3930361779, 4016923089, 4113052479, 925926767, 1755287528,
             802865554, 954171070, 3724185978, 173676273, 1414488795, 12664133,
             1784889697, 1303817078, 261610523, 941280008, 2571813643,
@@ -141,4 +342,14 @@ mod tests {
             assert_eq!(rng.next_u32(), e);
         }
     }
+
+    #[test]
+    fn advance_period() {
+        // The period is 2^64, so advancing by it does not change the state.
+        let mut rng1 = SplitMix64::from_seed_u64(0);
+        let mut rng2 = rng1.clone();
+        rng2.advance(1 << 64);
+        assert_eq!(rng1, rng2);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+    }
 }
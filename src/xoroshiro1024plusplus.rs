@@ -0,0 +1,352 @@
+use rand_core::impls::fill_bytes_via_next;
+use rand_core::le::read_u64_into;
+use rand_core::{SeedableRng, RngCore, Error};
+
+use {Seed1024, ZeroStateError};
+
+/// A xoroshiro1024++ random number generator.
+///
+/// The xoroshiro1024++ algorithm is not suitable for cryptographic purposes, but
+/// is very fast and has excellent statistical properties.
+///
+/// The algorithm used here is translated from [the `xoroshiro1024plusplus.c`
+/// reference source code](http://xoshiro.di.unimi.it/xoroshiro1024plusplus.c) by
+/// David Blackman and Sebastiano Vigna.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Xoroshiro1024PlusPlus {
+    s: [u64; 16],
+    p: usize,
+}
+
+impl_deserialize!(Xoroshiro1024PlusPlus { s: [u64; 16], p: usize },
+                  |state| state.s.iter().any(|&x| x != 0) && state.p < 16);
+impl_state_eq!(Xoroshiro1024PlusPlus);
+
+impl Xoroshiro1024PlusPlus {
+    /// Create a new `Xoroshiro1024PlusPlus` from its raw state words, like
+    /// `from_state`.
+    ///
+    /// This is convenient for creating generators in constants, where an
+    /// all-zero state results in a compile-time error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zero.
+    pub const fn new(s: [u64; 16]) -> Xoroshiro1024PlusPlus {
+        match Xoroshiro1024PlusPlus::from_state(s) {
+            Ok(rng) => rng,
+            Err(_) => panic!("the state must not be all zero"),
+        }
+    }
+
+    /// Create a new `Xoroshiro1024PlusPlus` from its raw state words.
+    ///
+    /// The words are in the order of the reference implementation, with its
+    /// circular buffer index `p` being 0. To resume the state `s` of the
+    /// reference implementation at a different index `p`, rotate the words so
+    /// that `s[p]` comes first, i.e. pass the words `s[(i + p) & 15]` for `i`
+    /// in `0..16`.
+    ///
+    /// This can be used to exchange states with other implementations. Fails
+    /// if the state is all zero, because the generator would only produce
+    /// zeros.
+    pub const fn from_state(s: [u64; 16])
+        -> Result<Xoroshiro1024PlusPlus, ZeroStateError>
+    {
+        if const_is_zero!(s) {
+            return Err(ZeroStateError);
+        }
+        Ok(Xoroshiro1024PlusPlus { s, p: 0 })
+    }
+
+    /// Return the raw state words, in the order of the reference
+    /// implementation with its circular buffer index `p` being 0.
+    ///
+    /// The circular buffer is rotated so that the word at the current position
+    /// comes first, so passing the result to `from_state` creates a generator
+    /// producing the same numbers.
+    pub const fn state(&self) -> [u64; 16] {
+        let mut s = [0; 16];
+        let mut i = 0;
+        while i < 16 {
+            s[i] = self.s[(i + self.p) & 15];
+            i += 1;
+        }
+        s
+    }
+
+    /// Seed a `Xoroshiro1024PlusPlus` from a `u64` using `SplitMix64`.
+    pub fn from_seed_u64(seed: u64) -> Xoroshiro1024PlusPlus {
+        from_splitmix!(seed)
+    }
+
+    impl_seeding!(Xoroshiro1024PlusPlus, 1024, Seed1024);
+
+    /// Return the next random `u64` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u64`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::Xoroshiro1024PlusPlus;
+    ///
+    /// const TABLE: [u64; 64] = {
+    ///     let mut rng = Xoroshiro1024PlusPlus::new([
+    ///         1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    ///     ]);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u64();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u64(mut self) -> (u64, Self) {
+        let s0 = self.s[(self.p + 1) & 15];
+        let s15 = self.s[self.p];
+        let result_plusplus = plusplus!(s15, s0, 23);
+        impl_xoroshiro_large!(self);
+        (result_plusplus, self)
+    }
+
+    impl_floats!(u64);
+
+    impl_fill!();
+
+    /// Jump forward, equivalently to 2^512 calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2^512 non-overlapping subsequences for
+    /// parallel computations.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::SeedableRng;
+    /// use xoshiro::Xoroshiro1024PlusPlus;
+    ///
+    /// let rng1 = Xoroshiro1024PlusPlus::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// rng2.jump();
+    /// let mut rng3 = rng2.clone();
+    /// rng3.jump();
+    /// # }
+    /// ```
+    pub fn jump(&mut self) {
+        impl_jump!(u64, self, [
+            0x931197d8e3177f17, 0xb59422e0b9138c5f, 0xf06a6afb49d668bb,
+            0xacb8a6412c8a1401, 0x12304ec85f0b3468, 0xb7dfe7079209891e,
+            0x405b7eec77d9eb14, 0x34ead68280c44e4a, 0xe0e4ba3e0ac9e366,
+            0x8f46eda8348905b7, 0x328bf4dbad90d6ff, 0xc8fd6fb31c9effc3,
+            0xe899d452d4b67652, 0x45f387286ade3205, 0x03864f454a8920bd,
+            0xa68fa28725b1b384
+        ]);
+    }
+
+    /// Jump forward, equivalently to 2^768 calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2^256 starting points, from each of which
+    /// `jump()` will generate 2^256 non-overlapping subsequences for parallel
+    /// distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u64, self, [
+            0x7374156360bbf00f, 0x4630c2efa3b3c1f6, 0x6654183a892786b1,
+            0x94f7bfcbfb0f1661, 0x27d8243d3d13eb2d, 0x9701730f3dfb300f,
+            0x2f293baae6f604ad, 0xa661831cb60cd8b6, 0x68280c77d9fe008c,
+            0x50554160f5ba9459, 0x2fc20b17ec7b2a9a, 0x49189bbdc8ec9f8f,
+            0x92a65bca41852cc1, 0xf46820dd0509c12a, 0x52b00c35fbf92185,
+            0x1e5b3b7f589e03c1
+        ]);
+    }
+
+    impl_streams!(Xoroshiro1024PlusPlus, next_u64, 256, 512);
+
+    /// Advance the state, equivalently to `n` calls to `next_u64()`.
+    ///
+    /// This takes time logarithmic in `n`, so it can be used to resume a
+    /// sequence at an exact position without generating all the numbers
+    /// before it.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::RngCore;
+    /// use xoshiro::Xoroshiro1024PlusPlus;
+    ///
+    /// let mut rng1 = Xoroshiro1024PlusPlus::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// for _ in 0..1000 {
+    ///     rng1.next_u64();
+    /// }
+    /// rng2.advance(1000);
+    /// assert_eq!(rng1.next_u64(), rng2.next_u64());
+    /// # }
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        impl_advance!(s p, self, next_u64, pow_x(n), ::gf2::XOROSHIRO1024);
+    }
+
+    /// Advance the state, equivalently to 2^`e` calls to `next_u64()`.
+    ///
+    /// This can be used to jump by distances that are too large for
+    /// `advance`.
+    pub fn advance_pow2(&mut self, e: u32) {
+        impl_advance!(s p, self, next_u64, pow_x_pow2(e), ::gf2::XOROSHIRO1024);
+    }
+
+    impl_prev!(u64 lower, s p, ::gf2::XOROSHIRO1024, |self| {
+        impl_xoroshiro_large_inv!(self);
+        let s0 = self.s[(self.p + 1) & 15];
+        let s15 = self.s[self.p];
+        plusplus!(s15, s0, 23)
+    });
+
+    impl_checkpoint!(Xoroshiro1024PlusPlus, next_u64,
+                     ::checkpoint::XOROSHIRO1024PLUSPLUS, s p, u64);
+}
+
+impl SeedableRng for Xoroshiro1024PlusPlus {
+    type Seed = Seed1024;
+
+    /// Create a new `Xoroshiro1024PlusPlus`.
+    ///
+    /// If `seed` is entirely 0, it is replaced by the first bytes generated by
+    /// `SplitMix64::from_seed_u64(0)`, so that the result is the same as for
+    /// `from_seed_u64(0)`. Use `try_from_seed` to reject such a seed instead.
+    #[inline]
+    fn from_seed(seed: Seed1024) -> Xoroshiro1024PlusPlus {
+        deal_with_zero_seed!(seed, Self);
+        let mut state = [0; 16];
+        read_u64_into(&seed.0, &mut state);
+        Xoroshiro1024PlusPlus { s: state, p: 0 }
+    }
+}
+
+impl RngCore for Xoroshiro1024PlusPlus {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let s0 = self.s[(self.p + 1) & 15];
+        let s15 = self.s[self.p];
+        let result_plusplus = plusplus!(s15, s0, 23);
+        impl_xoroshiro_large!(self);
+        result_plusplus
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via_next(self, dest);
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl_block_rng_core!(Xoroshiro1024PlusPlus, u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATE: [u64; 16] = [
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    ];
+
+    test_rng!(Xoroshiro1024PlusPlus, u64, new(STATE),
+              serde({ s: STATE, p: 3 },
+                    r#"{"s":[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16],"p":3}"#),
+              other(Xoroshiro1024StarStar), zero({ s: [0; 16], p: 0 }),
+              charpoly(XOROSHIRO1024), jumps(512, 768));
+
+    #[test]
+    fn reference() {
+        let mut seed = Seed1024([0; 128]);
+        for (i, word) in seed.0.chunks_mut(8).enumerate() {
+            word[0] = i as u8 + 1;
+        }
+        let mut rng = Xoroshiro1024PlusPlus::from_seed(seed);
+        // These values were produced with the reference implementation:
+        // http://xoshiro.di.unimi.it/xoroshiro1024plusplus.c
+        let expected = [
+            25165825, 1729382463093866496, 1729382469544706816,
+            2305896067134128920, 2882358539580539928, 3472347753827474720,
+            4049248931463568936, 8088996010033751856, 8778205716228747320,
+            10512250523491973488, 3058955393346585209, 4829029104734534545,
+            15823320274339792426, 8370049367947321154, 1133206330363230427,
+            12137637231248495219, 12250960224845351950, 14528824471047009190,
+            13274477229914197931, 11015462779189700542, 7387518644729620922,
+            1948062446964394375, 423473111899027327, 12765077007160514838,
+            15561225899362379789, 1401999715166886541, 14178401638997296831,
+            10760580332620499176, 6067588448132124895, 1360048824466108933,
+            10244254107372089669, 3242613093471700539, 581003863735325947,
+            9994494813334470546, 10781242928292267269, 8587928216511798000,
+            9877782096192255028, 13237059411866864031, 7630430785446126766,
+            6017192545939418858,
+        ];
+        for &e in &expected {
+            assert_eq!(rng.next_u64(), e);
+        }
+    }
+
+    #[test]
+    fn reference_state() {
+        // The state of the reference implementation after 5 steps from the
+        // state of `reference`, where its index `p` is 5.
+        let s = [
+            469762051, 9223372243516522499, 9223372209324557060,
+            53155085681413, 9223416499363054598, 13581579943347456, 7, 8, 9,
+            10, 11, 12, 13, 14, 15, 16,
+        ];
+        let p = 5;
+        let mut rotated = [0; 16];
+        for (i, x) in rotated.iter_mut().enumerate() {
+            *x = s[(i + p) & 15];
+        }
+        let mut rng = Xoroshiro1024PlusPlus::from_state(rotated).unwrap();
+        // These are the following values of the reference test.
+        let expected = [
+            3472347753827474720, 4049248931463568936, 8088996010033751856,
+            8778205716228747320, 10512250523491973488,
+        ];
+        for &e in &expected {
+            assert_eq!(rng.next_u64(), e);
+        }
+    }
+
+    #[test]
+    fn state_rotated() {
+        // The circular buffer of the restored generator starts at index 0.
+        let mut rng1 = Xoroshiro1024PlusPlus::from_seed_u64(1);
+        for _ in 0..3 {
+            rng1.next_u64();
+        }
+        let rng2 = Xoroshiro1024PlusPlus::from_state(rng1.state()).unwrap();
+        assert!(rng1.p != rng2.p);
+        assert_eq!(rng1, rng2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_index() {
+        assert!(::serde_json::from_str::<Xoroshiro1024PlusPlus>(
+            r#"{"s":[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16],"p":16}"#).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+use rand_core::impls::fill_bytes_via_next;
+use rand_core::le::read_u64_into;
+use rand_core::{SeedableRng, RngCore, Error};
+
+use {Seed1024, ZeroStateError};
+
+/// A xoroshiro1024* random number generator.
+///
+/// The xoroshiro1024* algorithm is not suitable for cryptographic purposes, but
+/// is very fast and has good statistical properties, besides a low linear
+/// complexity in the lowest bits.
+///
+/// The algorithm used here is translated from [the `xoroshiro1024star.c`
+/// reference source code](http://xoshiro.di.unimi.it/xoroshiro1024star.c) by
+/// David Blackman and Sebastiano Vigna.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Xoroshiro1024Star {
+    s: [u64; 16],
+    p: usize,
+}
+
+impl_deserialize!(Xoroshiro1024Star { s: [u64; 16], p: usize },
+                  |state| state.s.iter().any(|&x| x != 0) && state.p < 16);
+impl_state_eq!(Xoroshiro1024Star);
+
+impl Xoroshiro1024Star {
+    /// Create a new `Xoroshiro1024Star` from its raw state words, like
+    /// `from_state`.
+    ///
+    /// This is convenient for creating generators in constants, where an
+    /// all-zero state results in a compile-time error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zero.
+    pub const fn new(s: [u64; 16]) -> Xoroshiro1024Star {
+        match Xoroshiro1024Star::from_state(s) {
+            Ok(rng) => rng,
+            Err(_) => panic!("the state must not be all zero"),
+        }
+    }
+
+    /// Create a new `Xoroshiro1024Star` from its raw state words.
+    ///
+    /// The words are in the order of the reference implementation, with its
+    /// circular buffer index `p` being 0. To resume the state `s` of the
+    /// reference implementation at a different index `p`, rotate the words so
+    /// that `s[p]` comes first, i.e. pass the words `s[(i + p) & 15]` for `i`
+    /// in `0..16`.
+    ///
+    /// This can be used to exchange states with other implementations. Fails
+    /// if the state is all zero, because the generator would only produce
+    /// zeros.
+    pub const fn from_state(s: [u64; 16])
+        -> Result<Xoroshiro1024Star, ZeroStateError>
+    {
+        if const_is_zero!(s) {
+            return Err(ZeroStateError);
+        }
+        Ok(Xoroshiro1024Star { s, p: 0 })
+    }
+
+    /// Return the raw state words, in the order of the reference
+    /// implementation with its circular buffer index `p` being 0.
+    ///
+    /// The circular buffer is rotated so that the word at the current position
+    /// comes first, so passing the result to `from_state` creates a generator
+    /// producing the same numbers.
+    pub const fn state(&self) -> [u64; 16] {
+        let mut s = [0; 16];
+        let mut i = 0;
+        while i < 16 {
+            s[i] = self.s[(i + self.p) & 15];
+            i += 1;
+        }
+        s
+    }
+
+    /// Seed a `Xoroshiro1024Star` from a `u64` using `SplitMix64`.
+    pub fn from_seed_u64(seed: u64) -> Xoroshiro1024Star {
+        from_splitmix!(seed)
+    }
+
+    impl_seeding!(Xoroshiro1024Star, 1024, Seed1024);
+
+    /// Return the next random `u64` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u64`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::Xoroshiro1024Star;
+    ///
+    /// const TABLE: [u64; 64] = {
+    ///     let mut rng = Xoroshiro1024Star::new([
+    ///         1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    ///     ]);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u64();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u64(mut self) -> (u64, Self) {
+        let s0 = self.s[(self.p + 1) & 15];
+        let result_star = s0.wrapping_mul(0x9e3779b97f4a7c13);
+        impl_xoroshiro_large!(self);
+        (result_star, self)
+    }
+
+    impl_floats!(u64);
+
+    impl_fill!();
+
+    /// Jump forward, equivalently to 2^512 calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2^512 non-overlapping subsequences for
+    /// parallel computations.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::SeedableRng;
+    /// use xoshiro::Xoroshiro1024Star;
+    ///
+    /// let rng1 = Xoroshiro1024Star::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// rng2.jump();
+    /// let mut rng3 = rng2.clone();
+    /// rng3.jump();
+    /// # }
+    /// ```
+    pub fn jump(&mut self) {
+        impl_jump!(u64, self, [
+            0x931197d8e3177f17, 0xb59422e0b9138c5f, 0xf06a6afb49d668bb,
+            0xacb8a6412c8a1401, 0x12304ec85f0b3468, 0xb7dfe7079209891e,
+            0x405b7eec77d9eb14, 0x34ead68280c44e4a, 0xe0e4ba3e0ac9e366,
+            0x8f46eda8348905b7, 0x328bf4dbad90d6ff, 0xc8fd6fb31c9effc3,
+            0xe899d452d4b67652, 0x45f387286ade3205, 0x03864f454a8920bd,
+            0xa68fa28725b1b384
+        ]);
+    }
+
+    /// Jump forward, equivalently to 2^768 calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2^256 starting points, from each of which
+    /// `jump()` will generate 2^256 non-overlapping subsequences for parallel
+    /// distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u64, self, [
+            0x7374156360bbf00f, 0x4630c2efa3b3c1f6, 0x6654183a892786b1,
+            0x94f7bfcbfb0f1661, 0x27d8243d3d13eb2d, 0x9701730f3dfb300f,
+            0x2f293baae6f604ad, 0xa661831cb60cd8b6, 0x68280c77d9fe008c,
+            0x50554160f5ba9459, 0x2fc20b17ec7b2a9a, 0x49189bbdc8ec9f8f,
+            0x92a65bca41852cc1, 0xf46820dd0509c12a, 0x52b00c35fbf92185,
+            0x1e5b3b7f589e03c1
+        ]);
+    }
+
+    impl_streams!(Xoroshiro1024Star, next_u64, 256, 512);
+
+    /// Advance the state, equivalently to `n` calls to `next_u64()`.
+    ///
+    /// This takes time logarithmic in `n`, so it can be used to resume a
+    /// sequence at an exact position without generating all the numbers
+    /// before it.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::RngCore;
+    /// use xoshiro::Xoroshiro1024Star;
+    ///
+    /// let mut rng1 = Xoroshiro1024Star::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// for _ in 0..1000 {
+    ///     rng1.next_u64();
+    /// }
+    /// rng2.advance(1000);
+    /// assert_eq!(rng1.next_u64(), rng2.next_u64());
+    /// # }
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        impl_advance!(s p, self, next_u64, pow_x(n), ::gf2::XOROSHIRO1024);
+    }
+
+    /// Advance the state, equivalently to 2^`e` calls to `next_u64()`.
+    ///
+    /// This can be used to jump by distances that are too large for
+    /// `advance`.
+    pub fn advance_pow2(&mut self, e: u32) {
+        impl_advance!(s p, self, next_u64, pow_x_pow2(e), ::gf2::XOROSHIRO1024);
+    }
+
+    impl_prev!(u64 upper, s p, ::gf2::XOROSHIRO1024, |self| {
+        impl_xoroshiro_large_inv!(self);
+        let s0 = self.s[(self.p + 1) & 15];
+        s0.wrapping_mul(0x9e3779b97f4a7c13)
+    });
+
+    impl_checkpoint!(Xoroshiro1024Star, next_u64,
+                     ::checkpoint::XOROSHIRO1024STAR, s p, u64);
+}
+
+impl SeedableRng for Xoroshiro1024Star {
+    type Seed = Seed1024;
+
+    /// Create a new `Xoroshiro1024Star`.
+    ///
+    /// If `seed` is entirely 0, it is replaced by the first bytes generated by
+    /// `SplitMix64::from_seed_u64(0)`, so that the result is the same as for
+    /// `from_seed_u64(0)`. Use `try_from_seed` to reject such a seed instead.
+    #[inline]
+    fn from_seed(seed: Seed1024) -> Xoroshiro1024Star {
+        deal_with_zero_seed!(seed, Self);
+        let mut state = [0; 16];
+        read_u64_into(&seed.0, &mut state);
+        Xoroshiro1024Star { s: state, p: 0 }
+    }
+}
+
+impl RngCore for Xoroshiro1024Star {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        // The lowest bits have some linear dependencies, so we use the
+        // upper bits instead.
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let s0 = self.s[(self.p + 1) & 15];
+        let result_star = s0.wrapping_mul(0x9e3779b97f4a7c13);
+        impl_xoroshiro_large!(self);
+        result_star
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via_next(self, dest);
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl_block_rng_core!(Xoroshiro1024Star, u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATE: [u64; 16] = [
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    ];
+
+    test_rng!(Xoroshiro1024Star, u64, new(STATE),
+              serde({ s: STATE, p: 3 },
+                    r#"{"s":[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16],"p":3}"#),
+              other(Xoroshiro1024StarStar), zero({ s: [0; 16], p: 0 }),
+              charpoly(XOROSHIRO1024), jumps(512, 768));
+
+    #[test]
+    fn reference() {
+        let mut seed = Seed1024([0; 128]);
+        for (i, word) in seed.0.chunks_mut(8).enumerate() {
+            word[0] = i as u8 + 1;
+        }
+        let mut rng = Xoroshiro1024Star::from_seed(seed);
+        // These values were produced with the reference implementation:
+        // http://xoshiro.di.unimi.it/xoroshiro1024star.c
+        let expected = [
+            4354685564936845350, 15755400384260043833, 8709371129873690700,
+            1663341875487337567, 13064056694810536050, 6018027440424182917,
+            17418742259747381400, 10372713005361028267, 3326683750974675134,
+            14727398570297873617, 7681369315911520484, 635340061525167351,
+            12036054880848365834, 4990025626462012701, 16390740445785211184,
+            2635330079153943609, 17692509087601751097, 9138394901289773388,
+            1821890904158938463, 5633708026870903922, 11679123502487487365,
+            10781792243611619992, 11033327878264709547, 7916531711771111614,
+            2787218014906106721, 3494315987382424180, 8324417658613838519,
+            1101884614238798586, 8699929814397518653, 7584625745854562592,
+            17470521595215788729, 8287519456833398467, 4150298582337400146,
+            15533503609230714217, 12834971220940826979, 938871117874678726,
+            398223883652552791, 15478259296402647871, 6879907647939534629,
+            6324283853482371996,
+        ];
+        for &e in &expected {
+            assert_eq!(rng.next_u64(), e);
+        }
+    }
+
+    #[test]
+    fn reference_state() {
+        // The state of the reference implementation after 5 steps from the
+        // state of `reference`, where its index `p` is 5.
+        let s = [
+            469762051, 9223372243516522499, 9223372209324557060,
+            53155085681413, 9223416499363054598, 13581579943347456, 7, 8, 9,
+            10, 11, 12, 13, 14, 15, 16,
+        ];
+        let p = 5;
+        let mut rotated = [0; 16];
+        for (i, x) in rotated.iter_mut().enumerate() {
+            *x = s[(i + p) & 15];
+        }
+        let mut rng = Xoroshiro1024Star::from_state(rotated).unwrap();
+        // These are the following values of the reference test.
+        let expected = [
+            6018027440424182917, 17418742259747381400, 10372713005361028267,
+            3326683750974675134, 14727398570297873617,
+        ];
+        for &e in &expected {
+            assert_eq!(rng.next_u64(), e);
+        }
+    }
+
+    #[test]
+    fn state_rotated() {
+        // The circular buffer of the restored generator starts at index 0.
+        let mut rng1 = Xoroshiro1024Star::from_seed_u64(1);
+        for _ in 0..3 {
+            rng1.next_u64();
+        }
+        let rng2 = Xoroshiro1024Star::from_state(rng1.state()).unwrap();
+        assert!(rng1.p != rng2.p);
+        assert_eq!(rng1, rng2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_index() {
+        assert!(::serde_json::from_str::<Xoroshiro1024Star>(
+            r#"{"s":[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16],"p":16}"#).is_err());
+    }
+}
@@ -3,6 +3,8 @@ use rand_core::le::read_u64_into;
 use rand_core::impls::fill_bytes_via_next;
 use rand_core::{RngCore, SeedableRng};
 
+use ZeroStateError;
+
 /// A xoroshiro128+ random number generator.
 ///
 /// The xoroshiro128+ algorithm is not suitable for cryptographic purposes, but
@@ -13,18 +15,99 @@ use rand_core::{RngCore, SeedableRng};
 /// reference source code](http://xoshiro.di.unimi.it/xoroshiro128plus.c) by
 /// David Blackman and Sebastiano Vigna.
 #[allow(missing_copy_implementations)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 pub struct Xoroshiro128Plus {
     s0: u64,
     s1: u64,
 }
 
+impl_deserialize!(Xoroshiro128Plus { s0: u64, s1: u64 },
+                  |state| state.s0 != 0 || state.s1 != 0);
+
 impl Xoroshiro128Plus {
+    /// Create a new `Xoroshiro128Plus` from its raw state words, like
+    /// `from_state`.
+    ///
+    /// This is convenient for creating generators in constants, where an
+    /// all-zero state results in a compile-time error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zero.
+    pub const fn new(s: [u64; 2]) -> Xoroshiro128Plus {
+        match Xoroshiro128Plus::from_state(s) {
+            Ok(rng) => rng,
+            Err(_) => panic!("the state must not be all zero"),
+        }
+    }
+
+    /// Create a new `Xoroshiro128Plus` from its raw state words, in the
+    /// order of the reference implementation.
+    ///
+    /// This can be used to exchange states with other implementations. Fails
+    /// if the state is all zero, because the generator would only produce
+    /// zeros.
+    pub const fn from_state(s: [u64; 2])
+        -> Result<Xoroshiro128Plus, ZeroStateError>
+    {
+        if const_is_zero!(s) {
+            return Err(ZeroStateError);
+        }
+        Ok(Xoroshiro128Plus { s0: s[0], s1: s[1] })
+    }
+
+    /// Return the raw state words, in the order of the reference
+    /// implementation.
+    ///
+    /// This is the inverse of `from_state`.
+    pub const fn state(&self) -> [u64; 2] {
+        [self.s0, self.s1]
+    }
+
     /// Seed a `Xoroshiro128Plus` from a `u64` using `SplitMix64`.
     pub fn from_seed_u64(seed: u64) -> Xoroshiro128Plus {
         from_splitmix!(seed)
     }
 
+    impl_seeding!(Xoroshiro128Plus, 128, [u8; 16]);
+
+    /// Return the next random `u64` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u64`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::Xoroshiro128Plus;
+    ///
+    /// const TABLE: [u64; 64] = {
+    ///     let mut rng = Xoroshiro128Plus::new([1, 2]);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u64();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u64(mut self) -> (u64, Self) {
+        let r = self.s0.wrapping_add(self.s1);
+        impl_xoroshiro_u64!(self);
+        (r, self)
+    }
+
+    impl_floats!(u64);
+
+    impl_fill!();
+
     /// Jump forward, equivalently to 2^64 calls to `next_u64()`.
     ///
     /// This can be used to generate 2^64 non-overlapping subsequences for
@@ -56,6 +139,50 @@ impl Xoroshiro128Plus {
     pub fn long_jump(&mut self) {
         impl_jump!(u64, self, [0xd2a98b26625eee7b, 0xdddf9b1090aa7ac1]);
     }
+
+    impl_streams!(Xoroshiro128Plus, next_u64, 32, 64);
+
+    /// Advance the state, equivalently to `n` calls to `next_u64()`.
+    ///
+    /// This takes time logarithmic in `n`, so it can be used to resume a
+    /// sequence at an exact position without generating all the numbers
+    /// before it.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::RngCore;
+    /// use xoshiro::Xoroshiro128Plus;
+    ///
+    /// let mut rng1 = Xoroshiro128Plus::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// for _ in 0..1000 {
+    ///     rng1.next_u64();
+    /// }
+    /// rng2.advance(1000);
+    /// assert_eq!(rng1.next_u64(), rng2.next_u64());
+    /// # }
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        impl_advance!(s0 s1, self, next_u64, pow_x(n), ::gf2::XOROSHIRO128);
+    }
+
+    /// Advance the state, equivalently to 2^`e` calls to `next_u64()`.
+    ///
+    /// This can be used to jump by distances that are too large for
+    /// `advance`.
+    pub fn advance_pow2(&mut self, e: u32) {
+        impl_advance!(s0 s1, self, next_u64, pow_x_pow2(e), ::gf2::XOROSHIRO128);
+    }
+
+    impl_prev!(u64 upper, s0 s1, ::gf2::XOROSHIRO128, |self| {
+        impl_xoroshiro_u64_inv!(self);
+        self.s0.wrapping_add(self.s1)
+    });
+
+    impl_checkpoint!(Xoroshiro128Plus, next_u64,
+                     ::checkpoint::XOROSHIRO128PLUS, s0 s1, u64);
 }
 
 impl RngCore for Xoroshiro128Plus {
@@ -85,11 +212,16 @@ impl RngCore for Xoroshiro128Plus {
     }
 }
 
+impl_block_rng_core!(Xoroshiro128Plus, u64);
+
 impl SeedableRng for Xoroshiro128Plus {
     type Seed = [u8; 16];
 
-    /// Create a new `Xoroshiro128Plus`.  If `seed` is entirely 0, it will be
-    /// mapped to a different seed.
+    /// Create a new `Xoroshiro128Plus`.
+    ///
+    /// If `seed` is entirely 0, it is replaced by the first bytes generated by
+    /// `SplitMix64::from_seed_u64(0)`, so that the result is the same as for
+    /// `from_seed_u64(0)`. Use `try_from_seed` to reject such a seed instead.
     fn from_seed(seed: [u8; 16]) -> Xoroshiro128Plus {
         deal_with_zero_seed!(seed, Self);
         let mut s = [0; 2];
@@ -106,6 +238,11 @@ impl SeedableRng for Xoroshiro128Plus {
 mod tests {
     use super::*;
 
+    test_rng!(Xoroshiro128Plus, u64, new([1, 2]),
+              serde({ s0: 1, s1: 2 }, r#"{"s0":1,"s1":2}"#),
+              other(Xoroshiro128StarStar), zero({ s0: 0, s1: 0 }),
+              charpoly(XOROSHIRO128), jumps(64, 96));
+
     #[test]
     fn reference() {
         let mut rng = Xoroshiro128Plus::from_seed(
@@ -0,0 +1,258 @@
+use rand_core;
+use rand_core::le::read_u64_into;
+use rand_core::impls::fill_bytes_via_next;
+use rand_core::{RngCore, SeedableRng};
+
+use ZeroStateError;
+
+/// A xoroshiro128++ random number generator.
+///
+/// The xoroshiro128++ algorithm is not suitable for cryptographic purposes, but
+/// is very fast and has excellent statistical properties.
+///
+/// The algorithm used here is translated from [the `xoroshiro128plusplus.c`
+/// reference source code](http://xoshiro.di.unimi.it/xoroshiro128plusplus.c) by
+/// David Blackman and Sebastiano Vigna.
+#[allow(missing_copy_implementations)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Xoroshiro128PlusPlus {
+    s0: u64,
+    s1: u64,
+}
+
+impl_deserialize!(Xoroshiro128PlusPlus { s0: u64, s1: u64 },
+                  |state| state.s0 != 0 || state.s1 != 0);
+
+impl Xoroshiro128PlusPlus {
+    /// Create a new `Xoroshiro128PlusPlus` from its raw state words, like
+    /// `from_state`.
+    ///
+    /// This is convenient for creating generators in constants, where an
+    /// all-zero state results in a compile-time error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zero.
+    pub const fn new(s: [u64; 2]) -> Xoroshiro128PlusPlus {
+        match Xoroshiro128PlusPlus::from_state(s) {
+            Ok(rng) => rng,
+            Err(_) => panic!("the state must not be all zero"),
+        }
+    }
+
+    /// Create a new `Xoroshiro128PlusPlus` from its raw state words, in the
+    /// order of the reference implementation.
+    ///
+    /// This can be used to exchange states with other implementations. Fails
+    /// if the state is all zero, because the generator would only produce
+    /// zeros.
+    pub const fn from_state(s: [u64; 2])
+        -> Result<Xoroshiro128PlusPlus, ZeroStateError>
+    {
+        if const_is_zero!(s) {
+            return Err(ZeroStateError);
+        }
+        Ok(Xoroshiro128PlusPlus { s0: s[0], s1: s[1] })
+    }
+
+    /// Return the raw state words, in the order of the reference
+    /// implementation.
+    ///
+    /// This is the inverse of `from_state`.
+    pub const fn state(&self) -> [u64; 2] {
+        [self.s0, self.s1]
+    }
+
+    /// Seed a `Xoroshiro128PlusPlus` from a `u64` using `SplitMix64`.
+    pub fn from_seed_u64(seed: u64) -> Xoroshiro128PlusPlus {
+        from_splitmix!(seed)
+    }
+
+    impl_seeding!(Xoroshiro128PlusPlus, 128, [u8; 16]);
+
+    /// Return the next random `u64` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u64`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::Xoroshiro128PlusPlus;
+    ///
+    /// const TABLE: [u64; 64] = {
+    ///     let mut rng = Xoroshiro128PlusPlus::new([1, 2]);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u64();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u64(mut self) -> (u64, Self) {
+        let r = plusplus!(self.s0, self.s1, 17);
+        impl_xoroshiro_u64_plusplus!(self);
+        (r, self)
+    }
+
+    impl_floats!(u64);
+
+    impl_fill!();
+
+    /// Jump forward, equivalently to 2^64 calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2^64 non-overlapping subsequences for
+    /// parallel computations.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::SeedableRng;
+    /// use xoshiro::Xoroshiro128PlusPlus;
+    ///
+    /// let rng1 = Xoroshiro128PlusPlus::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// rng2.jump();
+    /// let mut rng3 = rng2.clone();
+    /// rng3.jump();
+    /// # }
+    /// ```
+    pub fn jump(&mut self) {
+        impl_jump!(u64, self, [0x2bd7a6a6e99c2ddc, 0x0992ccaf6a6fca05]);
+    }
+
+    /// Jump forward, equivalently to 2^96 calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2^32 starting points, from each of which
+    /// `jump()` will generate 2^32 non-overlapping subsequences for parallel
+    /// distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u64, self, [0x360fd5f2cf8d5d99, 0x9c6e6877736c46e3]);
+    }
+
+    impl_streams!(Xoroshiro128PlusPlus, next_u64, 32, 64);
+
+    /// Advance the state, equivalently to `n` calls to `next_u64()`.
+    ///
+    /// This takes time logarithmic in `n`, so it can be used to resume a
+    /// sequence at an exact position without generating all the numbers
+    /// before it.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::RngCore;
+    /// use xoshiro::Xoroshiro128PlusPlus;
+    ///
+    /// let mut rng1 = Xoroshiro128PlusPlus::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// for _ in 0..1000 {
+    ///     rng1.next_u64();
+    /// }
+    /// rng2.advance(1000);
+    /// assert_eq!(rng1.next_u64(), rng2.next_u64());
+    /// # }
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        impl_advance!(s0 s1, self, next_u64, pow_x(n), ::gf2::XOROSHIRO128PLUSPLUS);
+    }
+
+    /// Advance the state, equivalently to 2^`e` calls to `next_u64()`.
+    ///
+    /// This can be used to jump by distances that are too large for
+    /// `advance`.
+    pub fn advance_pow2(&mut self, e: u32) {
+        impl_advance!(s0 s1, self, next_u64, pow_x_pow2(e), ::gf2::XOROSHIRO128PLUSPLUS);
+    }
+
+    impl_prev!(u64 lower, s0 s1, ::gf2::XOROSHIRO128PLUSPLUS, |self| {
+        impl_xoroshiro_u64_plusplus_inv!(self);
+        plusplus!(self.s0, self.s1, 17)
+    });
+
+    impl_checkpoint!(Xoroshiro128PlusPlus, next_u64,
+                     ::checkpoint::XOROSHIRO128PLUSPLUS, s0 s1, u64);
+}
+
+impl RngCore for Xoroshiro128PlusPlus {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let r = plusplus!(self.s0, self.s1, 17);
+        impl_xoroshiro_u64_plusplus!(self);
+        r
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via_next(self, dest);
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl_block_rng_core!(Xoroshiro128PlusPlus, u64);
+
+impl SeedableRng for Xoroshiro128PlusPlus {
+    type Seed = [u8; 16];
+
+    /// Create a new `Xoroshiro128PlusPlus`.
+    ///
+    /// If `seed` is entirely 0, it is replaced by the first bytes generated by
+    /// `SplitMix64::from_seed_u64(0)`, so that the result is the same as for
+    /// `from_seed_u64(0)`. Use `try_from_seed` to reject such a seed instead.
+    fn from_seed(seed: [u8; 16]) -> Xoroshiro128PlusPlus {
+        deal_with_zero_seed!(seed, Self);
+        let mut s = [0; 2];
+        read_u64_into(&seed, &mut s);
+
+        Xoroshiro128PlusPlus {
+            s0: s[0],
+            s1: s[1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_rng!(Xoroshiro128PlusPlus, u64, new([1, 2]),
+              serde({ s0: 1, s1: 2 }, r#"{"s0":1,"s1":2}"#),
+              other(Xoroshiro128StarStar), zero({ s0: 0, s1: 0 }),
+              charpoly(XOROSHIRO128PLUSPLUS), jumps(64, 96));
+
+    #[test]
+    fn reference() {
+        let mut rng = Xoroshiro128PlusPlus::from_seed(
+            [1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+        // These values were produced with the reference implementation:
+        // http://xoshiro.di.unimi.it/xoroshiro128plusplus.c
+        let expected = [
+            393217, 669327710093319, 1732421326133921491, 11394790081659126983,
+            9555452776773192676, 3586421180005889563, 1691397964866707553,
+            10735626796753111697, 15216282715349408991, 14247243556711267923,
+        ];
+        for &e in &expected {
+            assert_eq!(rng.next_u64(), e);
+        }
+    }
+}
@@ -4,6 +4,8 @@ use rand_core::le::read_u32_into;
 use rand_core::impls::{fill_bytes_via_next, next_u64_via_u32};
 use rand_core::{RngCore, SeedableRng};
 
+use ZeroStateError;
+
 /// A Xoroshiro64** random number generator.
 ///
 /// The xoshiro64** algorithm is not suitable for cryptographic purposes, but
@@ -13,19 +15,197 @@ use rand_core::{RngCore, SeedableRng};
 /// reference source code](http://xoshiro.di.unimi.it/xoroshiro64starstar.c) by
 /// David Blackman and Sebastiano Vigna.
 #[allow(missing_copy_implementations)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 pub struct Xoroshiro64StarStar {
     s0: u32,
     s1: u32,
 }
 
+impl_deserialize!(Xoroshiro64StarStar { s0: u32, s1: u32 },
+                  |state| state.s0 != 0 || state.s1 != 0);
+
 impl Xoroshiro64StarStar {
-    /// Seed a `Xoroshiro64StarStar` from a `u64`.
+    /// Create a new `Xoroshiro64StarStar` from its raw state words, like
+    /// `from_state`.
+    ///
+    /// This is convenient for creating generators in constants, where an
+    /// all-zero state results in a compile-time error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zero.
+    pub const fn new(s: [u32; 2]) -> Xoroshiro64StarStar {
+        match Xoroshiro64StarStar::from_state(s) {
+            Ok(rng) => rng,
+            Err(_) => panic!("the state must not be all zero"),
+        }
+    }
+
+    /// Create a new `Xoroshiro64StarStar` from its raw state words, in the
+    /// order of the reference implementation.
+    ///
+    /// This can be used to exchange states with other implementations. Fails
+    /// if the state is all zero, because the generator would only produce
+    /// zeros.
+    pub const fn from_state(s: [u32; 2])
+        -> Result<Xoroshiro64StarStar, ZeroStateError>
+    {
+        if const_is_zero!(s) {
+            return Err(ZeroStateError);
+        }
+        Ok(Xoroshiro64StarStar { s0: s[0], s1: s[1] })
+    }
+
+    /// Return the raw state words, in the order of the reference
+    /// implementation.
+    ///
+    /// This is the inverse of `from_state`.
+    pub const fn state(&self) -> [u32; 2] {
+        [self.s0, self.s1]
+    }
+
+    /// Seed a `Xoroshiro64StarStar` from a `u64` by using it as the raw state.
+    ///
+    /// The seed is written to the state in little-endian byte order, so
+    /// small seeds result in similar states, and a seed of 0 is handled like
+    /// an all-zero seed by `from_seed`. This is kept to reproduce existing
+    /// streams; new code should use `from_seed_u64_v2` instead.
     pub fn from_seed_u64(seed: u64) -> Xoroshiro64StarStar {
         let mut s = [0; 8];
         LittleEndian::write_u64(&mut s, seed);
         Xoroshiro64StarStar::from_seed(s)
     }
+
+    impl_seeding!(Xoroshiro64StarStar, 64, [u8; 8]);
+
+    /// Seed a `Xoroshiro64StarStar` from a `u64` using `SplitMix64`.
+    ///
+    /// This is consistent with `from_seed_u64` of the other generators, but
+    /// produces different streams than `from_seed_u64` of this one, which is
+    /// kept unchanged to not break code relying on them.
+    pub fn from_seed_u64_v2(seed: u64) -> Xoroshiro64StarStar {
+        from_splitmix!(seed)
+    }
+
+    /// Return the next random `u32` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u32`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::Xoroshiro64StarStar;
+    ///
+    /// const TABLE: [u32; 64] = {
+    ///     let mut rng = Xoroshiro64StarStar::new([1, 2]);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u32();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u32(mut self) -> (u32, Self) {
+        let r = starstar_u32!(self.s0);
+        impl_xoroshiro_u32!(self);
+        (r, self)
+    }
+
+    impl_floats!(u32);
+
+    impl_fill!();
+
+    /// Jump forward, equivalently to 2^32 calls to `next_u32()`.
+    ///
+    /// This can be used to generate 2^32 non-overlapping subsequences for
+    /// parallel computations.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::SeedableRng;
+    /// use xoshiro::Xoroshiro64StarStar;
+    ///
+    /// let rng1 = Xoroshiro64StarStar::from_seed_u64(1);
+    /// let mut rng2 = rng1.clone();
+    /// rng2.jump();
+    /// let mut rng3 = rng2.clone();
+    /// rng3.jump();
+    /// # }
+    /// ```
+    pub fn jump(&mut self) {
+        // There is no jump polynomial in the reference implementation, this
+        // one was calculated from the characteristic polynomial.
+        impl_jump!(u32, self, [0x77fcd1a0, 0x4cbf99bd]);
+    }
+
+    /// Jump forward, equivalently to 2^48 calls to `next_u32()`.
+    ///
+    /// This can be used to generate 2^16 starting points, from each of which
+    /// `jump()` will generate 2^16 non-overlapping subsequences for parallel
+    /// distributed computations.
+    pub fn long_jump(&mut self) {
+        // There is no jump polynomial in the reference implementation, this
+        // one was calculated from the characteristic polynomial.
+        impl_jump!(u32, self, [0x3f1f8b95, 0xb4e7e463]);
+    }
+
+    impl_streams!(Xoroshiro64StarStar, next_u32, 16, 32,
+"Panics if `n` is 2^32 or larger. The period is 2^64 - 1, so only
+2^32 - 1 streams of 2^32 numbers fit into it without overlapping.",
+                  ::common::assert_xoroshiro64_streams);
+
+    /// Advance the state, equivalently to `n` calls to `next_u32()`.
+    ///
+    /// This takes time logarithmic in `n`, so it can be used to resume a
+    /// sequence at an exact position without generating all the numbers
+    /// before it.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::RngCore;
+    /// use xoshiro::Xoroshiro64StarStar;
+    ///
+    /// let mut rng1 = Xoroshiro64StarStar::from_seed_u64(1);
+    /// let mut rng2 = rng1.clone();
+    /// for _ in 0..1000 {
+    ///     rng1.next_u32();
+    /// }
+    /// rng2.advance(1000);
+    /// assert_eq!(rng1.next_u32(), rng2.next_u32());
+    /// # }
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        impl_advance!(s0 s1, self, next_u32, pow_x(n), ::gf2::XOROSHIRO64);
+    }
+
+    /// Advance the state, equivalently to 2^`e` calls to `next_u32()`.
+    ///
+    /// This can be used to jump by distances that are too large for
+    /// `advance`.
+    pub fn advance_pow2(&mut self, e: u32) {
+        impl_advance!(s0 s1, self, next_u32, pow_x_pow2(e), ::gf2::XOROSHIRO64);
+    }
+
+    impl_prev!(u32, s0 s1, ::gf2::XOROSHIRO64, |self| {
+        impl_xoroshiro_u32_inv!(self);
+        starstar_u32!(self.s0)
+    });
+
+    impl_checkpoint!(Xoroshiro64StarStar, next_u32,
+                     ::checkpoint::XOROSHIRO64STARSTAR, s0 s1, u32);
 }
 
 impl RngCore for Xoroshiro64StarStar {
@@ -53,11 +233,16 @@ impl RngCore for Xoroshiro64StarStar {
     }
 }
 
+impl_block_rng_core!(Xoroshiro64StarStar, u32);
+
 impl SeedableRng for Xoroshiro64StarStar {
     type Seed = [u8; 8];
 
-    /// Create a new `Xoroshiro64StarStar`.  If `seed` is entirely 0, it will be
-    /// mapped to a different seed.
+    /// Create a new `Xoroshiro64StarStar`.
+    ///
+    /// If `seed` is entirely 0, it is replaced by the first bytes generated by
+    /// `SplitMix64::from_seed_u64(0)`, so that the result is the same as for
+    /// `from_seed_u64(0)`. Use `try_from_seed` to reject such a seed instead.
     fn from_seed(seed: [u8; 8]) -> Xoroshiro64StarStar {
         deal_with_zero_seed!(seed, Self);
         let mut s = [0; 2];
@@ -74,6 +259,11 @@ impl SeedableRng for Xoroshiro64StarStar {
 mod tests {
     use super::*;
 
+    test_rng!(Xoroshiro64StarStar, u32, new([1, 2]),
+              serde({ s0: 1, s1: 2 }, r#"{"s0":1,"s1":2}"#),
+              other(Xoroshiro64Star), zero({ s0: 0, s1: 0 }),
+              charpoly(XOROSHIRO64), jumps(32, 48));
+
     #[test]
     fn reference() {
         let mut rng = Xoroshiro64StarStar::from_seed([1, 0, 0, 0, 2, 0, 0, 0]);
@@ -87,4 +277,56 @@ mod tests {
             assert_eq!(rng.next_u32(), e);
         }
     }
+
+    #[test]
+    fn from_seed_u64_v2() {
+        // The original seeding uses the seed as the raw state.
+        let mut rng1 = Xoroshiro64StarStar::from_seed_u64(1);
+        let mut rng2 = Xoroshiro64StarStar::from_state([1, 0]).unwrap();
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+
+        let mut rng1 = Xoroshiro64StarStar::from_seed_u64_v2(1);
+        let splitmix = ::SplitMix64::from_seed_u64(1);
+        let mut rng2 = Xoroshiro64StarStar::from_rng(splitmix).unwrap();
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+        let mut rng1 = Xoroshiro64StarStar::from_seed_u64_v2(0);
+        let mut rng2 = Xoroshiro64StarStar::from_seed_u64(0);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn streams_long_jump() {
+        // Streams are grouped by long jumps.
+        let rng = Xoroshiro64StarStar::from_seed_u64(1);
+        let streams = rng.streams((1 << 16) + 1);
+        let mut expected = rng.clone();
+        expected.long_jump();
+        assert_eq!(streams[1 << 16].clone().next_u32(), expected.next_u32());
+    }
+
+    #[test]
+    fn streams_bound() {
+        // Stream 2^32 - 1 would start here.
+        let rng = Xoroshiro64StarStar::from_seed_u64(1);
+        let mut last = rng.clone();
+        last.advance(((1 << 32) - 1) << 32);
+        // After 2^32 - 1 numbers, it would reach the start of the first
+        // stream, so only 2^32 - 1 streams fit into the period.
+        last.advance((1 << 32) - 1);
+        assert_eq!(last, rng);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn streams_max() {
+        ::common::assert_xoroshiro64_streams((1 << 32) - 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "too many streams")]
+    fn streams_too_many() {
+        Xoroshiro64StarStar::from_seed_u64(1).streams(1 << 32);
+    }
 }
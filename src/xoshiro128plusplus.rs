@@ -0,0 +1,251 @@
+use rand_core::impls::{next_u64_via_u32, fill_bytes_via_next};
+use rand_core::le::read_u32_into;
+use rand_core::{SeedableRng, RngCore, Error};
+
+use ZeroStateError;
+
+/// A xoshiro128++ random number generator.
+///
+/// The xoshiro128++ algorithm is not suitable for cryptographic purposes, but
+/// is very fast and has excellent statistical properties.
+///
+/// The algorithm used here is translated from [the `xoshiro128plusplus.c`
+/// reference source code](http://xoshiro.di.unimi.it/xoshiro128plusplus.c) by
+/// David Blackman and Sebastiano Vigna.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Xoshiro128PlusPlus {
+    s: [u32; 4],
+}
+
+impl_deserialize!(Xoshiro128PlusPlus { s: [u32; 4] },
+                  |state| state.s.iter().any(|&x| x != 0));
+
+impl Xoshiro128PlusPlus {
+    /// Create a new `Xoshiro128PlusPlus` from its raw state words, like
+    /// `from_state`.
+    ///
+    /// This is convenient for creating generators in constants, where an
+    /// all-zero state results in a compile-time error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zero.
+    pub const fn new(s: [u32; 4]) -> Xoshiro128PlusPlus {
+        match Xoshiro128PlusPlus::from_state(s) {
+            Ok(rng) => rng,
+            Err(_) => panic!("the state must not be all zero"),
+        }
+    }
+
+    /// Create a new `Xoshiro128PlusPlus` from its raw state words, in the
+    /// order of the reference implementation.
+    ///
+    /// This can be used to exchange states with other implementations. Fails
+    /// if the state is all zero, because the generator would only produce
+    /// zeros.
+    pub const fn from_state(s: [u32; 4])
+        -> Result<Xoshiro128PlusPlus, ZeroStateError>
+    {
+        if const_is_zero!(s) {
+            return Err(ZeroStateError);
+        }
+        Ok(Xoshiro128PlusPlus { s })
+    }
+
+    /// Return the raw state words, in the order of the reference
+    /// implementation.
+    ///
+    /// This is the inverse of `from_state`.
+    pub const fn state(&self) -> [u32; 4] {
+        self.s
+    }
+
+    /// Seed a `Xoshiro128PlusPlus` from a `u64` using `SplitMix64`.
+    pub fn from_seed_u64(seed: u64) -> Xoshiro128PlusPlus {
+        from_splitmix!(seed)
+    }
+
+    impl_seeding!(Xoshiro128PlusPlus, 128, [u8; 16]);
+
+    /// Return the next random `u32` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u32`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::Xoshiro128PlusPlus;
+    ///
+    /// const TABLE: [u32; 64] = {
+    ///     let mut rng = Xoshiro128PlusPlus::new([1, 2, 3, 4]);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u32();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u32(mut self) -> (u32, Self) {
+        let result_plusplus = plusplus!(self.s[0], self.s[3], 7);
+        impl_xoshiro_u32!(self);
+        (result_plusplus, self)
+    }
+
+    impl_floats!(u32);
+
+    impl_fill!();
+
+    /// Jump forward, equivalently to 2^64 calls to `next_u32()`.
+    ///
+    /// This can be used to generate 2^64 non-overlapping subsequences for
+    /// parallel computations.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::SeedableRng;
+    /// use xoshiro::Xoshiro128PlusPlus;
+    ///
+    /// let rng1 = Xoshiro128PlusPlus::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// rng2.jump();
+    /// let mut rng3 = rng2.clone();
+    /// rng3.jump();
+    /// # }
+    /// ```
+    pub fn jump(&mut self) {
+        impl_jump!(u32, self, [0x8764000b, 0xf542d2d3, 0x6fa035c3, 0x77f2db5b]);
+    }
+
+    /// Jump forward, equivalently to 2^96 calls to `next_u32()`.
+    ///
+    /// This can be used to generate 2^32 starting points, from each of which
+    /// `jump()` will generate 2^32 non-overlapping subsequences for parallel
+    /// distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u32, self, [0xb523952e, 0x0b6f099f, 0xccf5a0ef, 0x1c580662]);
+    }
+
+    impl_streams!(Xoshiro128PlusPlus, next_u32, 32, 64);
+
+    /// Advance the state, equivalently to `n` calls to `next_u32()`.
+    ///
+    /// This takes time logarithmic in `n`, so it can be used to resume a
+    /// sequence at an exact position without generating all the numbers
+    /// before it.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::RngCore;
+    /// use xoshiro::Xoshiro128PlusPlus;
+    ///
+    /// let mut rng1 = Xoshiro128PlusPlus::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// for _ in 0..1000 {
+    ///     rng1.next_u32();
+    /// }
+    /// rng2.advance(1000);
+    /// assert_eq!(rng1.next_u32(), rng2.next_u32());
+    /// # }
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        impl_advance!(s, self, next_u32, pow_x(n), ::gf2::XOSHIRO128);
+    }
+
+    /// Advance the state, equivalently to 2^`e` calls to `next_u32()`.
+    ///
+    /// This can be used to jump by distances that are too large for
+    /// `advance`.
+    pub fn advance_pow2(&mut self, e: u32) {
+        impl_advance!(s, self, next_u32, pow_x_pow2(e), ::gf2::XOSHIRO128);
+    }
+
+    impl_prev!(u32, s, ::gf2::XOSHIRO128, |self| {
+        impl_xoshiro_u32_inv!(self);
+        plusplus!(self.s[0], self.s[3], 7)
+    });
+
+    impl_checkpoint!(Xoshiro128PlusPlus, next_u32,
+                     ::checkpoint::XOSHIRO128PLUSPLUS, s, [u32; 4]);
+}
+
+impl SeedableRng for Xoshiro128PlusPlus {
+    type Seed = [u8; 16];
+
+    /// Create a new `Xoshiro128PlusPlus`.
+    ///
+    /// If `seed` is entirely 0, it is replaced by the first bytes generated by
+    /// `SplitMix64::from_seed_u64(0)`, so that the result is the same as for
+    /// `from_seed_u64(0)`. Use `try_from_seed` to reject such a seed instead.
+    #[inline]
+    fn from_seed(seed: [u8; 16]) -> Xoshiro128PlusPlus {
+        deal_with_zero_seed!(seed, Self);
+        let mut state = [0; 4];
+        read_u32_into(&seed, &mut state);
+        Xoshiro128PlusPlus { s: state }
+    }
+}
+
+impl RngCore for Xoshiro128PlusPlus {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let result_plusplus = plusplus!(self.s[0], self.s[3], 7);
+        impl_xoshiro_u32!(self);
+        result_plusplus
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        next_u64_via_u32(self)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via_next(self, dest);
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl_block_rng_core!(Xoshiro128PlusPlus, u32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_rng!(Xoshiro128PlusPlus, u32, new([1, 2, 3, 4]),
+              serde({ s: [1, 2, 3, 4] }, r#"{"s":[1,2,3,4]}"#),
+              other(Xoshiro128StarStar), zero({ s: [0; 4] }),
+              charpoly(XOSHIRO128), jumps(64, 96));
+
+    #[test]
+    fn reference() {
+        let mut rng = Xoshiro128PlusPlus::from_seed(
+            [1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0]);
+        // These values were produced with the reference implementation:
+        // http://xoshiro.di.unimi.it/xoshiro128plusplus.c
+        let expected = [
+            641, 1573767, 3222811527, 3517856514, 836907274, 4247214768,
+            3867114732, 1355841295, 495546011, 621204420,
+        ];
+        for &e in &expected {
+            assert_eq!(rng.next_u32(), e);
+        }
+    }
+}
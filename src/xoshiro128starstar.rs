@@ -2,6 +2,8 @@ use rand_core::impls::{next_u64_via_u32, fill_bytes_via_next};
 use rand_core::le::read_u32_into;
 use rand_core::{SeedableRng, RngCore, Error};
 
+use ZeroStateError;
+
 /// A xoshiro128** random number generator.
 ///
 /// The xoshiro128** algorithm is not suitable for cryptographic purposes, but
@@ -10,17 +12,98 @@ use rand_core::{SeedableRng, RngCore, Error};
 /// The algorithm used here is translated from [the `xoshiro128starstar.c`
 /// reference source code](http://xoshiro.di.unimi.it/xoshiro128starstar.c) by
 /// David Blackman and Sebastiano Vigna.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 pub struct Xoshiro128StarStar {
     s: [u32; 4],
 }
 
+impl_deserialize!(Xoshiro128StarStar { s: [u32; 4] },
+                  |state| state.s.iter().any(|&x| x != 0));
+
 impl Xoshiro128StarStar {
+    /// Create a new `Xoshiro128StarStar` from its raw state words, like
+    /// `from_state`.
+    ///
+    /// This is convenient for creating generators in constants, where an
+    /// all-zero state results in a compile-time error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zero.
+    pub const fn new(s: [u32; 4]) -> Xoshiro128StarStar {
+        match Xoshiro128StarStar::from_state(s) {
+            Ok(rng) => rng,
+            Err(_) => panic!("the state must not be all zero"),
+        }
+    }
+
+    /// Create a new `Xoshiro128StarStar` from its raw state words, in the
+    /// order of the reference implementation.
+    ///
+    /// This can be used to exchange states with other implementations. Fails
+    /// if the state is all zero, because the generator would only produce
+    /// zeros.
+    pub const fn from_state(s: [u32; 4])
+        -> Result<Xoshiro128StarStar, ZeroStateError>
+    {
+        if const_is_zero!(s) {
+            return Err(ZeroStateError);
+        }
+        Ok(Xoshiro128StarStar { s })
+    }
+
+    /// Return the raw state words, in the order of the reference
+    /// implementation.
+    ///
+    /// This is the inverse of `from_state`.
+    pub const fn state(&self) -> [u32; 4] {
+        self.s
+    }
+
     /// Seed a `Xoshiro128StarStar` from a `u64` using `SplitMix64`.
     pub fn from_seed_u64(seed: u64) -> Xoshiro128StarStar {
         from_splitmix!(seed)
     }
 
+    impl_seeding!(Xoshiro128StarStar, 128, [u8; 16]);
+
+    /// Return the next random `u32` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u32`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::Xoshiro128StarStar;
+    ///
+    /// const TABLE: [u32; 64] = {
+    ///     let mut rng = Xoshiro128StarStar::new([1, 2, 3, 4]);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u32();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u32(mut self) -> (u32, Self) {
+        let result_starstar = starstar_u64!(self.s[0]);
+        impl_xoshiro_u32!(self);
+        (result_starstar, self)
+    }
+
+    impl_floats!(u32);
+
+    impl_fill!();
+
     /// Jump forward, equivalently to 2^64 calls to `next_u32()`.
     ///
     /// This can be used to generate 2^64 non-overlapping subsequences for
@@ -43,13 +126,69 @@ impl Xoshiro128StarStar {
     pub fn jump(&mut self) {
         impl_jump!(u32, self, [0x8764000b, 0xf542d2d3, 0x6fa035c3, 0x77f2db5b]);
     }
+
+    /// Jump forward, equivalently to 2^96 calls to `next_u32()`.
+    ///
+    /// This can be used to generate 2^32 starting points, from each of which
+    /// `jump()` will generate 2^32 non-overlapping subsequences for parallel
+    /// distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u32, self, [0xb523952e, 0x0b6f099f, 0xccf5a0ef, 0x1c580662]);
+    }
+
+    impl_streams!(Xoshiro128StarStar, next_u32, 32, 64);
+
+    /// Advance the state, equivalently to `n` calls to `next_u32()`.
+    ///
+    /// This takes time logarithmic in `n`, so it can be used to resume a
+    /// sequence at an exact position without generating all the numbers
+    /// before it.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::RngCore;
+    /// use xoshiro::Xoshiro128StarStar;
+    ///
+    /// let mut rng1 = Xoshiro128StarStar::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// for _ in 0..1000 {
+    ///     rng1.next_u32();
+    /// }
+    /// rng2.advance(1000);
+    /// assert_eq!(rng1.next_u32(), rng2.next_u32());
+    /// # }
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        impl_advance!(s, self, next_u32, pow_x(n), ::gf2::XOSHIRO128);
+    }
+
+    /// Advance the state, equivalently to 2^`e` calls to `next_u32()`.
+    ///
+    /// This can be used to jump by distances that are too large for
+    /// `advance`.
+    pub fn advance_pow2(&mut self, e: u32) {
+        impl_advance!(s, self, next_u32, pow_x_pow2(e), ::gf2::XOSHIRO128);
+    }
+
+    impl_prev!(u32, s, ::gf2::XOSHIRO128, |self| {
+        impl_xoshiro_u32_inv!(self);
+        starstar_u64!(self.s[0])
+    });
+
+    impl_checkpoint!(Xoshiro128StarStar, next_u32,
+                     ::checkpoint::XOSHIRO128STARSTAR, s, [u32; 4]);
 }
 
 impl SeedableRng for Xoshiro128StarStar {
     type Seed = [u8; 16];
 
-    /// Create a new `Xoshiro128StarStar`.  If `seed` is entirely 0, it will be
-    /// mapped to a different seed.
+    /// Create a new `Xoshiro128StarStar`.
+    ///
+    /// If `seed` is entirely 0, it is replaced by the first bytes generated by
+    /// `SplitMix64::from_seed_u64(0)`, so that the result is the same as for
+    /// `from_seed_u64(0)`. Use `try_from_seed` to reject such a seed instead.
     #[inline]
     fn from_seed(seed: [u8; 16]) -> Xoshiro128StarStar {
         deal_with_zero_seed!(seed, Self);
@@ -84,10 +223,17 @@ impl RngCore for Xoshiro128StarStar {
     }
 }
 
+impl_block_rng_core!(Xoshiro128StarStar, u32);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    test_rng!(Xoshiro128StarStar, u32, new([1, 2, 3, 4]),
+              serde({ s: [1, 2, 3, 4] }, r#"{"s":[1,2,3,4]}"#),
+              other(Xoshiro128Plus), zero({ s: [0; 4] }),
+              charpoly(XOSHIRO128), jumps(64, 96));
+
     #[test]
     fn reference() {
         let mut rng = Xoshiro128StarStar::from_seed(
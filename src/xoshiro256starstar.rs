@@ -2,6 +2,8 @@ use rand_core::impls::fill_bytes_via_next;
 use rand_core::le::read_u64_into;
 use rand_core::{SeedableRng, RngCore, Error};
 
+use ZeroStateError;
+
 /// A xoshiro256** random number generator.
 ///
 /// The xoshiro256** algorithm is not suitable for cryptographic purposes, but
@@ -10,17 +12,98 @@ use rand_core::{SeedableRng, RngCore, Error};
 /// The algorithm used here is translated from [the `xoshiro256starstar.c`
 /// reference source code](http://xoshiro.di.unimi.it/xoshiro256starstar.c) by
 /// David Blackman and Sebastiano Vigna.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 pub struct Xoshiro256StarStar {
     s: [u64; 4],
 }
 
+impl_deserialize!(Xoshiro256StarStar { s: [u64; 4] },
+                  |state| state.s.iter().any(|&x| x != 0));
+
 impl Xoshiro256StarStar {
+    /// Create a new `Xoshiro256StarStar` from its raw state words, like
+    /// `from_state`.
+    ///
+    /// This is convenient for creating generators in constants, where an
+    /// all-zero state results in a compile-time error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zero.
+    pub const fn new(s: [u64; 4]) -> Xoshiro256StarStar {
+        match Xoshiro256StarStar::from_state(s) {
+            Ok(rng) => rng,
+            Err(_) => panic!("the state must not be all zero"),
+        }
+    }
+
+    /// Create a new `Xoshiro256StarStar` from its raw state words, in the
+    /// order of the reference implementation.
+    ///
+    /// This can be used to exchange states with other implementations. Fails
+    /// if the state is all zero, because the generator would only produce
+    /// zeros.
+    pub const fn from_state(s: [u64; 4])
+        -> Result<Xoshiro256StarStar, ZeroStateError>
+    {
+        if const_is_zero!(s) {
+            return Err(ZeroStateError);
+        }
+        Ok(Xoshiro256StarStar { s })
+    }
+
+    /// Return the raw state words, in the order of the reference
+    /// implementation.
+    ///
+    /// This is the inverse of `from_state`.
+    pub const fn state(&self) -> [u64; 4] {
+        self.s
+    }
+
     /// Seed a `Xoshiro256StarStar` from a `u64` using `SplitMix64`.
     pub fn from_seed_u64(seed: u64) -> Xoshiro256StarStar {
         from_splitmix!(seed)
     }
 
+    impl_seeding!(Xoshiro256StarStar, 256, [u8; 32]);
+
+    /// Return the next random `u64` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u64`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::Xoshiro256StarStar;
+    ///
+    /// const TABLE: [u64; 64] = {
+    ///     let mut rng = Xoshiro256StarStar::new([1, 2, 3, 4]);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u64();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u64(mut self) -> (u64, Self) {
+        let result_starstar = starstar_u64!(self.s[1]);
+        impl_xoshiro_u64!(self);
+        (result_starstar, self)
+    }
+
+    impl_floats!(u64);
+
+    impl_fill!();
+
     /// Jump forward, equivalently to 2^128 calls to `next_u64()`.
     ///
     /// This can be used to generate 2^128 non-overlapping subsequences for
@@ -58,13 +141,60 @@ impl Xoshiro256StarStar {
             0x77710069854ee241, 0x39109bb02acbe635
         ]);
     }
+
+    impl_streams!(Xoshiro256StarStar, next_u64, 64, 128);
+
+    /// Advance the state, equivalently to `n` calls to `next_u64()`.
+    ///
+    /// This takes time logarithmic in `n`, so it can be used to resume a
+    /// sequence at an exact position without generating all the numbers
+    /// before it.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::RngCore;
+    /// use xoshiro::Xoshiro256StarStar;
+    ///
+    /// let mut rng1 = Xoshiro256StarStar::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// for _ in 0..1000 {
+    ///     rng1.next_u64();
+    /// }
+    /// rng2.advance(1000);
+    /// assert_eq!(rng1.next_u64(), rng2.next_u64());
+    /// # }
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        impl_advance!(s, self, next_u64, pow_x(n), ::gf2::XOSHIRO256);
+    }
+
+    /// Advance the state, equivalently to 2^`e` calls to `next_u64()`.
+    ///
+    /// This can be used to jump by distances that are too large for
+    /// `advance`.
+    pub fn advance_pow2(&mut self, e: u32) {
+        impl_advance!(s, self, next_u64, pow_x_pow2(e), ::gf2::XOSHIRO256);
+    }
+
+    impl_prev!(u64 lower, s, ::gf2::XOSHIRO256, |self| {
+        impl_xoshiro_u64_inv!(self);
+        starstar_u64!(self.s[1])
+    });
+
+    impl_checkpoint!(Xoshiro256StarStar, next_u64,
+                     ::checkpoint::XOSHIRO256STARSTAR, s, [u64; 4]);
 }
 
 impl SeedableRng for Xoshiro256StarStar {
     type Seed = [u8; 32];
 
-    /// Create a new `Xoshiro256StarStar`.  If `seed` is entirely 0, it will be
-    /// mapped to a different seed.
+    /// Create a new `Xoshiro256StarStar`.
+    ///
+    /// If `seed` is entirely 0, it is replaced by the first bytes generated by
+    /// `SplitMix64::from_seed_u64(0)`, so that the result is the same as for
+    /// `from_seed_u64(0)`. Use `try_from_seed` to reject such a seed instead.
     #[inline]
     fn from_seed(seed: [u8; 32]) -> Xoshiro256StarStar {
         deal_with_zero_seed!(seed, Self);
@@ -99,10 +229,17 @@ impl RngCore for Xoshiro256StarStar {
     }
 }
 
+impl_block_rng_core!(Xoshiro256StarStar, u64);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    test_rng!(Xoshiro256StarStar, u64, new([1, 2, 3, 4]),
+              serde({ s: [1, 2, 3, 4] }, r#"{"s":[1,2,3,4]}"#),
+              other(Xoshiro256Plus), zero({ s: [0; 4] }),
+              charpoly(XOSHIRO256), jumps(128, 192));
+
     #[test]
     fn reference() {
         let mut rng = Xoshiro256StarStar::from_seed(
@@ -2,7 +2,7 @@ use rand_core::impls::fill_bytes_via_next;
 use rand_core::le::read_u64_into;
 use rand_core::{SeedableRng, RngCore, Error};
 
-use Seed512;
+use {Seed512, ZeroStateError};
 
 /// A xoshiro512+ random number generator.
 ///
@@ -13,17 +13,98 @@ use Seed512;
 /// The algorithm used here is translated from [the `xoshiro512plus.c`
 /// reference source code](http://xoshiro.di.unimi.it/xoshiro512plus.c) by
 /// David Blackman and Sebastiano Vigna.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 pub struct Xoshiro512Plus {
     s: [u64; 8],
 }
 
+impl_deserialize!(Xoshiro512Plus { s: [u64; 8] },
+                  |state| state.s.iter().any(|&x| x != 0));
+
 impl Xoshiro512Plus {
+    /// Create a new `Xoshiro512Plus` from its raw state words, like
+    /// `from_state`.
+    ///
+    /// This is convenient for creating generators in constants, where an
+    /// all-zero state results in a compile-time error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zero.
+    pub const fn new(s: [u64; 8]) -> Xoshiro512Plus {
+        match Xoshiro512Plus::from_state(s) {
+            Ok(rng) => rng,
+            Err(_) => panic!("the state must not be all zero"),
+        }
+    }
+
+    /// Create a new `Xoshiro512Plus` from its raw state words, in the order
+    /// of the reference implementation.
+    ///
+    /// This can be used to exchange states with other implementations. Fails
+    /// if the state is all zero, because the generator would only produce
+    /// zeros.
+    pub const fn from_state(s: [u64; 8])
+        -> Result<Xoshiro512Plus, ZeroStateError>
+    {
+        if const_is_zero!(s) {
+            return Err(ZeroStateError);
+        }
+        Ok(Xoshiro512Plus { s })
+    }
+
+    /// Return the raw state words, in the order of the reference
+    /// implementation.
+    ///
+    /// This is the inverse of `from_state`.
+    pub const fn state(&self) -> [u64; 8] {
+        self.s
+    }
+
     /// Seed a `Xoshiro512Plus` from a `u64` using `SplitMix64`.
     pub fn from_seed_u64(seed: u64) -> Xoshiro512Plus {
         from_splitmix!(seed)
     }
 
+    impl_seeding!(Xoshiro512Plus, 512, Seed512);
+
+    /// Return the next random `u64` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u64`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::Xoshiro512Plus;
+    ///
+    /// const TABLE: [u64; 64] = {
+    ///     let mut rng = Xoshiro512Plus::new([1, 2, 3, 4, 5, 6, 7, 8]);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u64();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u64(mut self) -> (u64, Self) {
+        let result_plus = self.s[0].wrapping_add(self.s[2]);
+        impl_xoshiro_large!(self);
+        (result_plus, self)
+    }
+
+    impl_floats!(u64);
+
+    impl_fill!();
+
     /// Jump forward, equivalently to 2^256 calls to `next_u64()`.
     ///
     /// This can be used to generate 2^256 non-overlapping subsequences for
@@ -50,13 +131,73 @@ impl Xoshiro512Plus {
             0x53851efdb6df0aaf, 0x1ebbc8b23eaf25db
         ]);
     }
+
+    /// Jump forward, equivalently to 2^384 calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2^128 starting points, from each of which
+    /// `jump()` will generate 2^128 non-overlapping subsequences for parallel
+    /// distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u64, self, [
+            0x11467fef8f921d28, 0xa2a819f2e79c8ea8, 0xa8299fc284b3959a,
+            0xb4d347340ca63ee1, 0x1cb0940bedbff6ce, 0xd956c5c4fa1f8e17,
+            0x915e38fd4eda93bc, 0x5b3ccdfa5d7daca5
+        ]);
+    }
+
+    impl_streams!(Xoshiro512Plus, next_u64, 128, 256);
+
+    /// Advance the state, equivalently to `n` calls to `next_u64()`.
+    ///
+    /// This takes time logarithmic in `n`, so it can be used to resume a
+    /// sequence at an exact position without generating all the numbers
+    /// before it.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::RngCore;
+    /// use xoshiro::Xoshiro512Plus;
+    ///
+    /// let mut rng1 = Xoshiro512Plus::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// for _ in 0..1000 {
+    ///     rng1.next_u64();
+    /// }
+    /// rng2.advance(1000);
+    /// assert_eq!(rng1.next_u64(), rng2.next_u64());
+    /// # }
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        impl_advance!(s, self, next_u64, pow_x(n), ::gf2::XOSHIRO512);
+    }
+
+    /// Advance the state, equivalently to 2^`e` calls to `next_u64()`.
+    ///
+    /// This can be used to jump by distances that are too large for
+    /// `advance`.
+    pub fn advance_pow2(&mut self, e: u32) {
+        impl_advance!(s, self, next_u64, pow_x_pow2(e), ::gf2::XOSHIRO512);
+    }
+
+    impl_prev!(u64 lower, s, ::gf2::XOSHIRO512, |self| {
+        impl_xoshiro_large_inv!(self);
+        self.s[0].wrapping_add(self.s[2])
+    });
+
+    impl_checkpoint!(Xoshiro512Plus, next_u64,
+                     ::checkpoint::XOSHIRO512PLUS, s, [u64; 8]);
 }
 
 impl SeedableRng for Xoshiro512Plus {
     type Seed = Seed512;
 
-    /// Create a new `Xoshiro512Plus`.  If `seed` is entirely 0, it will be
-    /// mapped to a different seed.
+    /// Create a new `Xoshiro512Plus`.
+    ///
+    /// If `seed` is entirely 0, it is replaced by the first bytes generated by
+    /// `SplitMix64::from_seed_u64(0)`, so that the result is the same as for
+    /// `from_seed_u64(0)`. Use `try_from_seed` to reject such a seed instead.
     #[inline]
     fn from_seed(seed: Seed512) -> Xoshiro512Plus {
         deal_with_zero_seed!(seed, Self);
@@ -91,10 +232,18 @@ impl RngCore for Xoshiro512Plus {
     }
 }
 
+impl_block_rng_core!(Xoshiro512Plus, u64);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    test_rng!(Xoshiro512Plus, u64, new([1, 2, 3, 4, 5, 6, 7, 8]),
+              serde({ s: [1, 2, 3, 4, 5, 6, 7, 8] },
+                    r#"{"s":[1,2,3,4,5,6,7,8]}"#),
+              other(Xoshiro512StarStar), zero({ s: [0; 8] }),
+              charpoly(XOSHIRO512), jumps(256, 384));
+
     #[test]
     fn reference() {
         let mut rng = Xoshiro512Plus::from_seed(Seed512(
@@ -0,0 +1,265 @@
+use rand_core::impls::fill_bytes_via_next;
+use rand_core::le::read_u64_into;
+use rand_core::{SeedableRng, RngCore, Error};
+
+use {Seed512, ZeroStateError};
+
+/// A xoshiro512++ random number generator.
+///
+/// The xoshiro512++ algorithm is not suitable for cryptographic purposes, but
+/// is very fast and has excellent statistical properties.
+///
+/// The algorithm used here is translated from [the `xoshiro512plusplus.c`
+/// reference source code](http://xoshiro.di.unimi.it/xoshiro512plusplus.c) by
+/// David Blackman and Sebastiano Vigna.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct Xoshiro512PlusPlus {
+    s: [u64; 8],
+}
+
+impl_deserialize!(Xoshiro512PlusPlus { s: [u64; 8] },
+                  |state| state.s.iter().any(|&x| x != 0));
+
+impl Xoshiro512PlusPlus {
+    /// Create a new `Xoshiro512PlusPlus` from its raw state words, like
+    /// `from_state`.
+    ///
+    /// This is convenient for creating generators in constants, where an
+    /// all-zero state results in a compile-time error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zero.
+    pub const fn new(s: [u64; 8]) -> Xoshiro512PlusPlus {
+        match Xoshiro512PlusPlus::from_state(s) {
+            Ok(rng) => rng,
+            Err(_) => panic!("the state must not be all zero"),
+        }
+    }
+
+    /// Create a new `Xoshiro512PlusPlus` from its raw state words, in the
+    /// order of the reference implementation.
+    ///
+    /// This can be used to exchange states with other implementations. Fails
+    /// if the state is all zero, because the generator would only produce
+    /// zeros.
+    pub const fn from_state(s: [u64; 8])
+        -> Result<Xoshiro512PlusPlus, ZeroStateError>
+    {
+        if const_is_zero!(s) {
+            return Err(ZeroStateError);
+        }
+        Ok(Xoshiro512PlusPlus { s })
+    }
+
+    /// Return the raw state words, in the order of the reference
+    /// implementation.
+    ///
+    /// This is the inverse of `from_state`.
+    pub const fn state(&self) -> [u64; 8] {
+        self.s
+    }
+
+    /// Seed a `Xoshiro512PlusPlus` from a `u64` using `SplitMix64`.
+    pub fn from_seed_u64(seed: u64) -> Xoshiro512PlusPlus {
+        from_splitmix!(seed)
+    }
+
+    impl_seeding!(Xoshiro512PlusPlus, 512, Seed512);
+
+    /// Return the next random `u64` and the advanced generator.
+    ///
+    /// This is the same as `RngCore::next_u64`, but is a `const fn`, so it can
+    /// be used to generate tables of random numbers at compile time:
+    ///
+    /// ```
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use xoshiro::Xoshiro512PlusPlus;
+    ///
+    /// const TABLE: [u64; 64] = {
+    ///     let mut rng = Xoshiro512PlusPlus::new([1, 2, 3, 4, 5, 6, 7, 8]);
+    ///     let mut table = [0; 64];
+    ///     let mut i = 0;
+    ///     while i < table.len() {
+    ///         let (x, next) = rng.const_next_u64();
+    ///         table[i] = x;
+    ///         rng = next;
+    ///         i += 1;
+    ///     }
+    ///     table
+    /// };
+    /// # let _ = TABLE;
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn const_next_u64(mut self) -> (u64, Self) {
+        let result_plusplus = plusplus!(self.s[2], self.s[0], 17);
+        impl_xoshiro_large!(self);
+        (result_plusplus, self)
+    }
+
+    impl_floats!(u64);
+
+    impl_fill!();
+
+    /// Jump forward, equivalently to 2^256 calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2^256 non-overlapping subsequences for
+    /// parallel computations.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::SeedableRng;
+    /// use xoshiro::Xoshiro512PlusPlus;
+    ///
+    /// let rng1 = Xoshiro512PlusPlus::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// rng2.jump();
+    /// let mut rng3 = rng2.clone();
+    /// rng3.jump();
+    /// # }
+    /// ```
+    pub fn jump(&mut self) {
+        impl_jump!(u64, self, [
+            0x33ed89b6e7a353f9, 0x760083d7955323be, 0x2837f2fbb5f22fae,
+            0x4b8c5674d309511c, 0xb11ac47a7ba28c25, 0xf1be7667092bcc1c,
+            0x53851efdb6df0aaf, 0x1ebbc8b23eaf25db
+        ]);
+    }
+
+    /// Jump forward, equivalently to 2^384 calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2^128 starting points, from each of which
+    /// `jump()` will generate 2^128 non-overlapping subsequences for parallel
+    /// distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u64, self, [
+            0x11467fef8f921d28, 0xa2a819f2e79c8ea8, 0xa8299fc284b3959a,
+            0xb4d347340ca63ee1, 0x1cb0940bedbff6ce, 0xd956c5c4fa1f8e17,
+            0x915e38fd4eda93bc, 0x5b3ccdfa5d7daca5
+        ]);
+    }
+
+    impl_streams!(Xoshiro512PlusPlus, next_u64, 128, 256);
+
+    /// Advance the state, equivalently to `n` calls to `next_u64()`.
+    ///
+    /// This takes time logarithmic in `n`, so it can be used to resume a
+    /// sequence at an exact position without generating all the numbers
+    /// before it.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate xoshiro;
+    /// # fn main() {
+    /// use rand::RngCore;
+    /// use xoshiro::Xoshiro512PlusPlus;
+    ///
+    /// let mut rng1 = Xoshiro512PlusPlus::from_seed_u64(0);
+    /// let mut rng2 = rng1.clone();
+    /// for _ in 0..1000 {
+    ///     rng1.next_u64();
+    /// }
+    /// rng2.advance(1000);
+    /// assert_eq!(rng1.next_u64(), rng2.next_u64());
+    /// # }
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        impl_advance!(s, self, next_u64, pow_x(n), ::gf2::XOSHIRO512);
+    }
+
+    /// Advance the state, equivalently to 2^`e` calls to `next_u64()`.
+    ///
+    /// This can be used to jump by distances that are too large for
+    /// `advance`.
+    pub fn advance_pow2(&mut self, e: u32) {
+        impl_advance!(s, self, next_u64, pow_x_pow2(e), ::gf2::XOSHIRO512);
+    }
+
+    impl_prev!(u64 lower, s, ::gf2::XOSHIRO512, |self| {
+        impl_xoshiro_large_inv!(self);
+        plusplus!(self.s[2], self.s[0], 17)
+    });
+
+    impl_checkpoint!(Xoshiro512PlusPlus, next_u64,
+                     ::checkpoint::XOSHIRO512PLUSPLUS, s, [u64; 8]);
+}
+
+
+impl SeedableRng for Xoshiro512PlusPlus {
+    type Seed = Seed512;
+
+    /// Create a new `Xoshiro512PlusPlus`.
+    ///
+    /// If `seed` is entirely 0, it is replaced by the first bytes generated by
+    /// `SplitMix64::from_seed_u64(0)`, so that the result is the same as for
+    /// `from_seed_u64(0)`. Use `try_from_seed` to reject such a seed instead.
+    #[inline]
+    fn from_seed(seed: Seed512) -> Xoshiro512PlusPlus {
+        deal_with_zero_seed!(seed, Self);
+        let mut state = [0; 8];
+        read_u64_into(&seed.0, &mut state);
+        Xoshiro512PlusPlus { s: state }
+    }
+}
+
+impl RngCore for Xoshiro512PlusPlus {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let result_plusplus = plusplus!(self.s[2], self.s[0], 17);
+        impl_xoshiro_large!(self);
+        result_plusplus
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_via_next(self, dest);
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl_block_rng_core!(Xoshiro512PlusPlus, u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_rng!(Xoshiro512PlusPlus, u64, new([1, 2, 3, 4, 5, 6, 7, 8]),
+              serde({ s: [1, 2, 3, 4, 5, 6, 7, 8] },
+                    r#"{"s":[1,2,3,4,5,6,7,8]}"#),
+              other(Xoshiro512StarStar), zero({ s: [0; 8] }),
+              charpoly(XOSHIRO512), jumps(256, 384));
+
+    #[test]
+    fn reference() {
+        let mut rng = Xoshiro512PlusPlus::from_seed(Seed512(
+            [1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0,
+             3, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0,
+             5, 0, 0, 0, 0, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0,
+             7, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0]));
+        // These values were produced with the reference implementation:
+        // http://xoshiro.di.unimi.it/xoshiro512plusplus.c
+        let expected = [
+            524291, 1048578, 539099140, 3299073855497, 6917532603230064654,
+            7494048333530275843, 14418333309547923463, 10960079161595355914,
+            18279570946505382726, 10209173166699159237,
+        ];
+        for &e in &expected {
+            assert_eq!(rng.next_u64(), e);
+        }
+    }
+}